@@ -0,0 +1,19 @@
+use std::{thread, time};
+
+use chess::board::Board;
+
+pub fn alienify_output_text(output_string: &str) {
+    print!("{}", output_string);
+    let sleep_time = time::Duration::from_millis(150);
+    thread::sleep(sleep_time);
+    println!("");
+}
+
+pub fn alienify_board(board: &Board) {
+    //the board comes out one row at a time, like it's being beamed down.
+    let sleep_time = time::Duration::from_millis(150);
+    for line in board.to_string().lines() {
+        println!("{}", line);
+        thread::sleep(sleep_time);
+    }
+}
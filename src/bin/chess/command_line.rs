@@ -0,0 +1,159 @@
+use chess::{
+    board::Board,
+    fen_parser::digest_string_to_board,
+    polyglot::{polyglot_key, PolyglotBook},
+    tablebase::Tablebase,
+};
+
+pub fn run_perft_command(arguments: &[String]) {
+    let depth: u32 = match arguments.first().map(|depth_string| depth_string.parse()) {
+        Some(Ok(depth)) => depth,
+        _ => {
+            println!("Usage: chess --perft <depth> [fen]");
+            return;
+        }
+    };
+
+    //a fen has spaces in it, so everything after the depth belongs to it.
+    let board = if arguments.len() > 1 {
+        match digest_string_to_board(arguments[1..].join(" ")) {
+            Ok(board) => board,
+            Err(fen_error) => {
+                println!("Oops! That fen string couldn't be parsed: {}", fen_error);
+                return;
+            }
+        }
+    } else {
+        Board::default()
+    };
+
+    let mut total_nodes = 0;
+    for (divided_move, node_count) in board.divide(depth) {
+        println!("{}: {}", divided_move, node_count);
+        total_nodes += node_count;
+    }
+
+    println!();
+    println!("Nodes searched: {}", total_nodes);
+}
+
+//one line per book move, heaviest first, with its share of the position's total weight.
+pub fn book_move_lines(book: &PolyglotBook, board: &Board) -> Vec<String> {
+    let book_moves = book.book_moves(board);
+    if book_moves.is_empty() {
+        return vec!["The book has no moves for this position.".to_string()];
+    }
+    let total_weight: u64 = book_moves
+        .iter()
+        .map(|book_move| book_move.weight as u64)
+        .sum();
+    book_moves
+        .iter()
+        .map(|book_move| {
+            let share = if total_weight == 0 {
+                0.0
+            } else {
+                book_move.weight as f64 * 100.0 / total_weight as f64
+            };
+            format!(
+                "{} ({}): weight {} ({:.1}%)",
+                book_move.chess_move.to_san(board),
+                book_move.chess_move,
+                book_move.weight,
+                share
+            )
+        })
+        .collect()
+}
+
+pub fn run_book_command(arguments: &[String]) {
+    let book_path = match arguments.first() {
+        Some(book_path) => book_path,
+        None => {
+            println!("Usage: chess --book <book.bin> [fen]");
+            return;
+        }
+    };
+    let book = match PolyglotBook::open(book_path) {
+        Ok(book) => book,
+        Err(io_error) => {
+            println!("Oops! That book couldn't be read: {}", io_error);
+            return;
+        }
+    };
+
+    let board = if arguments.len() > 1 {
+        match digest_string_to_board(arguments[1..].join(" ")) {
+            Ok(board) => board,
+            Err(fen_error) => {
+                println!("Oops! That fen string couldn't be parsed: {}", fen_error);
+                return;
+            }
+        }
+    } else {
+        Board::default()
+    };
+
+    println!("Polyglot key: {:016x}", polyglot_key(&board));
+    for book_line in book_move_lines(&book, &board) {
+        println!("{}", book_line);
+    }
+}
+
+pub fn run_tablebase_command(arguments: &[String]) {
+    let directory = match arguments.first() {
+        Some(directory) => directory,
+        None => {
+            println!("Usage: chess --tablebase <syzygy directory> [fen]");
+            return;
+        }
+    };
+    let tablebase = match Tablebase::open(directory) {
+        Ok(tablebase) => tablebase,
+        Err(io_error) => {
+            println!("Oops! That directory couldn't be read: {}", io_error);
+            return;
+        }
+    };
+
+    let board = if arguments.len() > 1 {
+        match digest_string_to_board(arguments[1..].join(" ")) {
+            Ok(board) => board,
+            Err(fen_error) => {
+                println!("Oops! That fen string couldn't be parsed: {}", fen_error);
+                return;
+            }
+        }
+    } else {
+        Board::default()
+    };
+
+    match tablebase.probe_wdl(&board) {
+        Ok(wdl) => println!("{:?} to move: {}", board.get_turn(), wdl),
+        Err(reason) => {
+            println!(
+                "Oops! The tablebase can't answer for this position: {}",
+                reason
+            );
+            return;
+        }
+    }
+    match tablebase.probe_dtz(&board) {
+        Ok(dtz) => println!("Distance to zero: {} half moves", dtz),
+        Err(reason) => println!("No distance to zero: {}", reason),
+    }
+    match tablebase.tablebase_moves(&board) {
+        Ok(tablebase_moves) => {
+            for tablebase_move in tablebase_moves {
+                println!(
+                    "{} ({}): {}, distance to zero {}",
+                    tablebase_move.chess_move.to_san(&board),
+                    tablebase_move.chess_move,
+                    tablebase_move.wdl,
+                    tablebase_move.dtz
+                );
+            }
+        }
+        Err(reason) => println!("The moves couldn't be ranked: {}", reason),
+    }
+}
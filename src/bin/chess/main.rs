@@ -0,0 +1,29 @@
+mod base_tools;
+mod command_line;
+mod play_structure;
+
+use std::{
+    env,
+    io::{stdin, stdout},
+};
+
+use chess::uci::run_uci;
+
+use command_line::{run_book_command, run_perft_command, run_tablebase_command};
+use play_structure::run_chess_interface;
+
+fn main() {
+    let arguments: Vec<String> = env::args().collect();
+
+    match arguments.get(1).map(|argument| argument.as_str()) {
+        Some("--perft") => run_perft_command(&arguments[2..]),
+        Some("--book") => run_book_command(&arguments[2..]),
+        Some("--tablebase") => run_tablebase_command(&arguments[2..]),
+        Some("--uci") => {
+            if let Err(io_error) = run_uci(stdin().lock(), &mut stdout()) {
+                eprintln!("The UCI connection broke: {}", io_error);
+            }
+        }
+        _ => run_chess_interface(),
+    }
+}
@@ -0,0 +1,818 @@
+use std::{
+    fs::File,
+    io::{stdin, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chess::{
+    board::*,
+    evaluation::evaluation_breakdown,
+    fen_parser::{ingest_fen_file, path_exists},
+    game::{pgn_date_today, Game},
+    interface::{parse_promotion_piece, parse_square},
+    moves::Move,
+    pgn_parser::read_pgn_file,
+    pieces::PieceColor,
+    polyglot::PolyglotBook,
+    rules::promotion_required,
+    search::{search_with_history, SearchLimits},
+};
+
+use crate::base_tools::{alienify_board, alienify_output_text};
+use crate::command_line::book_move_lines;
+
+//the side the computer plays, and how hard it thinks about each move.
+struct ComputerPlayer {
+    color: PieceColor,
+    limits: SearchLimits,
+}
+
+const STRENGTH_LEVELS: u32 = 5;
+
+//the computer takes a draw when its search doesn't think it's ahead by more than this.
+const DRAW_ACCEPTANCE_SCORE: i32 = 0;
+
+fn search_limits_for_strength(strength: u32) -> SearchLimits {
+    //the weaker levels only look a move or two ahead. The strongest gets a few seconds to go as deep as it can.
+    match strength {
+        1 => SearchLimits {
+            opt_depth: Some(1),
+            opt_time: None,
+        },
+        2 => SearchLimits {
+            opt_depth: Some(2),
+            opt_time: None,
+        },
+        3 => SearchLimits {
+            opt_depth: Some(3),
+            opt_time: Some(Duration::from_secs(2)),
+        },
+        4 => SearchLimits {
+            opt_depth: Some(4),
+            opt_time: Some(Duration::from_secs(3)),
+        },
+        _ => SearchLimits {
+            opt_depth: None,
+            opt_time: Some(Duration::from_secs(5)),
+        },
+    }
+}
+
+fn play_computer_move(game: &mut Game, computer: &ComputerPlayer, board_states: &mut Vec<Board>) {
+    let previous_turn_board = game.current_board().clone();
+    let search_result = search_with_history(
+        &previous_turn_board,
+        game.position_history(),
+        computer.limits,
+    );
+    if game.can_answer_draw_offer() {
+        if search_result.score <= DRAW_ACCEPTANCE_SCORE {
+            game.accept_draw();
+            alienify_output_text("The computer accepts your draw offer.");
+            return;
+        }
+        game.decline_draw();
+        alienify_output_text("The computer declines your draw offer.");
+    }
+    let computer_move = match search_result.opt_best_move {
+        Some(computer_move) => computer_move,
+        None => return,
+    };
+    let computer_san = computer_move.to_san(&previous_turn_board);
+    game.play_move(&computer_move);
+    board_states.push(previous_turn_board);
+    alienify_output_text(&format!("The computer plays {}", computer_san));
+}
+
+fn play_chess(mut game: Game, opt_computer: Option<ComputerPlayer>) {
+    let white_turn_string = "WHITE TO MOVE";
+    let black_turn_string = "BLACK TO MOVE";
+    let mut game_not_over = true;
+
+    let mut board_states: Vec<Board> = Vec::new();
+    //the book is only read the first time someone asks for it, then kept for the rest of the game.
+    let mut opt_book: Option<PolyglotBook> = None;
+    while game_not_over {
+        if let Some(computer) = opt_computer.as_ref() {
+            if game.current_board().get_turn() == computer.color {
+                alienify_output_text("The computer is thinking...");
+                play_computer_move(&mut game, computer, &mut board_states);
+                if let Some(outcome) = game.outcome() {
+                    alienify_board(game.current_board());
+                    alienify_output_text(&format!("{}. The game is over.", outcome));
+                    game_not_over = false;
+                }
+                continue;
+            }
+        }
+
+        let current_board = game.current_board().clone();
+        let current_turn = current_board.get_turn().clone();
+        let turn_string = if current_turn == PieceColor::White {
+            white_turn_string
+        } else {
+            black_turn_string
+        };
+        let not_first_turn = board_states.len() > 0;
+        println!("{}", turn_string);
+        alienify_board(&current_board);
+        if game.can_answer_draw_offer() {
+            alienify_output_text(&format!(
+                "{:?} has offered a draw. Making a move turns it down.",
+                current_turn.get_inverse_color()
+            ));
+        }
+
+        alienify_output_text("Please enter a selection:");
+        alienify_output_text("1: move");
+        alienify_output_text("2: show legal moves from square");
+        alienify_output_text("3: show previous turn");
+        alienify_output_text("4: Save this game");
+        alienify_output_text("5: surrender");
+        alienify_output_text("6: claim a draw");
+        alienify_output_text("7: offer a draw");
+        alienify_output_text("8: evaluate position");
+        alienify_output_text("9: show opening book moves");
+        if game.can_answer_draw_offer() {
+            alienify_output_text("10: answer the draw offer");
+        }
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        let indication_number: u32 = match indication.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("It appears you entered something that wasn't a positive integer. Oops!");
+                continue;
+            }
+        };
+
+        match indication_number {
+            1 => {
+                move_piece_on_board(&mut game, &mut board_states);
+                if !game.is_over() {
+                    if game.can_claim_threefold_repetition() {
+                        alienify_output_text(
+                            "This position has come up three times. Either player may claim a draw.",
+                        );
+                    }
+                    if game.can_claim_fifty_move_rule() {
+                        alienify_output_text(
+                            "Fifty moves have gone by without a capture or a pawn move. Either player may claim a draw.",
+                        );
+                    }
+                }
+            }
+            2 => {
+                query_legal_squares(&current_board);
+            }
+            3 => {
+                if not_first_turn {
+                    show_previous_board_state(&board_states);
+                } else {
+                    println!("Oops! Looks like it's still the first turn.");
+                }
+            }
+            4 => {
+                save_game(&game);
+                game_not_over = false;
+            }
+            5 => {
+                println!("{} has surrendered.", current_board.get_turn_full());
+                game.resign(current_turn);
+            }
+            6 => {
+                if !game.claim_threefold_repetition() && !game.claim_fifty_move_rule() {
+                    alienify_output_text(
+                        "There's no draw to claim yet. A position has to come up three times, or fifty moves go by without a capture or a pawn move.",
+                    );
+                }
+            }
+            7 => {
+                if game.offer_draw() {
+                    let answerer = if opt_computer.is_some() {
+                        "the computer will answer it"
+                    } else {
+                        "your opponent can answer it"
+                    };
+                    alienify_output_text(&format!(
+                        "Your draw offer is on the table. Make your move and {}.",
+                        answerer
+                    ));
+                } else {
+                    alienify_output_text("There's already a draw offer on the table.");
+                }
+            }
+            8 => {
+                for breakdown_line in evaluation_breakdown(&current_board).to_string().lines() {
+                    alienify_output_text(breakdown_line);
+                }
+            }
+            9 => show_book_moves(&mut opt_book, &current_board),
+            10 if game.can_answer_draw_offer() => answer_draw_offer(&mut game),
+            _ => alienify_output_text("Hey friend, I think you entered an invalid number"),
+        }
+
+        if let Some(outcome) = game.outcome() {
+            alienify_output_text(&format!("{}. The game is over.", outcome));
+            game_not_over = false;
+        }
+    }
+}
+
+fn show_book_moves(opt_book: &mut Option<PolyglotBook>, board: &Board) {
+    if opt_book.is_none() {
+        alienify_output_text("Please input a path for a polyglot book:");
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        match PolyglotBook::open(indication.trim()) {
+            Ok(book) => *opt_book = Some(book),
+            Err(io_error) => {
+                println!("Oops! I couldn't read {}: {}", indication.trim(), io_error);
+                return;
+            }
+        }
+    }
+
+    if let Some(book) = opt_book.as_ref() {
+        for book_line in book_move_lines(book, board) {
+            alienify_output_text(&book_line);
+        }
+    }
+}
+
+fn answer_draw_offer(game: &mut Game) {
+    loop {
+        alienify_output_text("Do you accept the draw? (y/n)");
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        match indication.trim().to_lowercase().as_str() {
+            "y" | "yes" => {
+                game.accept_draw();
+                return;
+            }
+            "n" | "no" => {
+                game.decline_draw();
+                alienify_output_text("The draw offer has been declined. Play on!");
+                return;
+            }
+            _ => alienify_output_text("Please answer with y or n."),
+        }
+    }
+}
+
+fn show_previous_board_state(previous_states: &Vec<Board>) {
+    let turn_text = format!("You are on turn {}", previous_states.len() - 1);
+    loop {
+        alienify_output_text(&turn_text);
+        alienify_output_text("Choose one of the following:");
+        alienify_output_text("1: Show previous turn");
+        alienify_output_text("2: Show turn N");
+        alienify_output_text("3: Exit");
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        let indication_number: u32 = match indication.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("It appears you entered something that wasn't a positive integer. Oops!");
+                continue;
+            }
+        };
+
+        match indication_number {
+            1 => match previous_states.last() {
+                Some(previous_state) => {
+                    println!("Previous turn:");
+                    alienify_board(previous_state);
+                    break;
+                }
+                None => {
+                    panic!("Oops! Looks like there both is, and isn't a previous board state!");
+                }
+            },
+            2 => {
+                show_specific_turn_state(previous_states);
+                break;
+            }
+            3 => {
+                break;
+            }
+            _ => {
+                println!("Oops! That wasn't one of the options.");
+            }
+        }
+    }
+}
+
+fn show_specific_turn_state(previous_states: &Vec<Board>) {
+    loop {
+        let turn_text = format!("You are on turn {}", previous_states.len() - 1);
+        alienify_output_text(&turn_text);
+        alienify_output_text("Please enter a turn to view, or X to exit: ");
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        if indication.to_lowercase().chars().nth(0).unwrap() == 'x' {
+            break;
+        }
+
+        let indication_number: usize = match indication.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                alienify_output_text(
+                    "It appears you entered something that wasn't a positive integer. Oops!",
+                );
+                continue;
+            }
+        };
+
+        if indication_number == 0 || indication_number > previous_states.len() {
+            alienify_output_text(
+                "Oops! Looks like you entered a number outside the range of previous turns!",
+            );
+            continue;
+        }
+
+        match previous_states.get(indication_number - 1) {
+            Some(board_state) => {
+                let formatted_turn_output = format!(
+                    "On turn {} the state of the game was:\n{}",
+                    indication_number, board_state
+                );
+                alienify_output_text(&formatted_turn_output);
+            }
+            None => {
+                alienify_output_text("Somehow you access an in-range, but non existant board!");
+            }
+        }
+    }
+}
+
+fn move_piece_on_board(game: &mut Game, board_states: &mut Vec<Board>) -> MoveResult {
+    let mut final_result = MoveResult::CompletedSafely;
+    loop {
+        alienify_output_text(
+            "Please enter a move in the form: a3 b3, or e7 e8 q to promote a pawn. Algebraic moves like Nf3 or exd8=Q work too. Otherwise enter X to exit.",
+        );
+
+        let mut indication = String::new();
+        let previous_turn_board = game.current_board().clone();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        if indication.to_lowercase().chars().nth(0).unwrap() == 'x' {
+            break;
+        }
+
+        match parse_move_input(&indication, &previous_turn_board) {
+            Ok(requested_move) => {
+                if requested_move.opt_promotion.is_none()
+                    && promotion_required(
+                        &requested_move.from,
+                        &requested_move.to,
+                        &previous_turn_board,
+                    )
+                {
+                    alienify_output_text(
+                        "That pawn needs to promote! Add the piece you want on the end, like e7 e8 q.",
+                    );
+                    continue;
+                }
+                final_result = game.play_move(&requested_move);
+                match final_result {
+                    MoveResult::WrongTurn => {
+                        println!(
+                            "Oops! It looks like you tried to move the wrong piece. It's {}'s turn",
+                            previous_turn_board.get_turn_full()
+                        );
+                    }
+                    MoveResult::MoveIllegal | MoveResult::EmptySquare => {
+                        alienify_output_text(
+                            "It appears that that was an illegal move! I'm sorry.",
+                        );
+                    }
+                    _ => {
+                        board_states.push(previous_turn_board);
+                        break;
+                    }
+                }
+            }
+            Err(input_error) => {
+                println!("That input format appears to be incorrect: {}", input_error)
+            }
+        }
+    }
+    final_result
+}
+
+fn parse_move_input(indication: &str, current_board: &Board) -> Result<Move, &'static str> {
+    let input_parts = indication.split_whitespace().collect::<Vec<&str>>();
+
+    if input_parts.len() == 1 {
+        //a single word has to be algebraic notation, like Nf3 or O-O.
+        return current_board.parse_san(input_parts[0]);
+    }
+
+    if input_parts.len() != 2 && input_parts.len() != 3 {
+        return Err("incorrect number of parts");
+    }
+
+    let from = parse_square(input_parts[0])?;
+    let to = parse_square(input_parts[1])?;
+    let opt_promotion = match input_parts.get(2) {
+        Some(promotion_string) => Some(parse_promotion_piece(promotion_string)?),
+        None => None,
+    };
+
+    Ok(Move::new(from, to, opt_promotion))
+}
+
+pub fn query_legal_squares(current_board: &Board) {
+    loop {
+        println!("Please enter coordinates, or X to quit:");
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        if indication.to_lowercase().chars().nth(0).unwrap() == 'x' {
+            break;
+        }
+
+        match parse_square(&indication[0..2]) {
+            Ok(coordinates) => {
+                alienify_output_text("Legal Moves: ");
+                current_board.show_me_legal_squares(&coordinates);
+                break;
+            }
+            Err(_) => alienify_output_text(
+                "Please input a valid square in the form of one letter, and one number",
+            ),
+        }
+    }
+}
+
+pub fn run_chess_interface() {
+    let mut should_keep_running = true;
+
+    while should_keep_running {
+        alienify_output_text("Please enter a selection:");
+        alienify_output_text("1: Play Chess");
+        alienify_output_text("2: Play vs computer");
+        alienify_output_text("3: Play Chess960");
+        alienify_output_text("4: Import Fen File");
+        alienify_output_text("5: Import PGN File");
+        alienify_output_text("6: Exit");
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        let indication_number: u32 = match indication.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("It appears you entered something that wasn't a positive integer. Oops!");
+                continue;
+            }
+        };
+
+        match indication_number {
+            1 => {
+                let mut game = Game::new();
+                game.set_tag("Date", &pgn_date_today());
+                play_chess(game, None);
+            }
+            2 => {
+                let computer = choose_computer_player();
+                let mut game = Game::new();
+                game.set_tag("Date", &pgn_date_today());
+                play_chess(game, Some(computer));
+            }
+            3 => {
+                play_random_chess960();
+            }
+            4 => {
+                handle_fen_import();
+            }
+            5 => {
+                handle_pgn_import();
+            }
+            6 => {
+                println!("Goodbye!");
+                should_keep_running = false;
+            }
+            _ => {
+                alienify_output_text("Hey friend, I think you entered an invalid number");
+            }
+        };
+    }
+}
+
+fn play_random_chess960() {
+    //the clock's nanoseconds are random enough to pick a starting position with.
+    let position_number = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default()
+        % CHESS960_POSITIONS;
+    let starting_board = match Board::chess960(position_number) {
+        Ok(starting_board) => starting_board,
+        Err(reason) => {
+            println!("Oops! {}", reason);
+            return;
+        }
+    };
+    alienify_output_text(&format!("Starting Chess960 position {}", position_number));
+
+    let mut game = Game::from_board(starting_board);
+    game.set_tag("Date", &pgn_date_today());
+    game.set_tag("Variant", "Chess960");
+    play_chess(game, None);
+}
+
+fn read_menu_number(prompt_lines: &[&str], highest: u32) -> u32 {
+    loop {
+        for prompt_line in prompt_lines {
+            alienify_output_text(prompt_line);
+        }
+
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        match indication.trim().parse::<u32>() {
+            Ok(num) if (1..=highest).contains(&num) => return num,
+            _ => alienify_output_text("Hey friend, I think you entered an invalid number"),
+        }
+    }
+}
+
+fn choose_computer_player() -> ComputerPlayer {
+    let human_color = match read_menu_number(
+        &[
+            "Which color would you like to play?",
+            "1: White",
+            "2: Black",
+        ],
+        2,
+    ) {
+        1 => PieceColor::White,
+        _ => PieceColor::Black,
+    };
+    let strength_prompt = format!(
+        "How strong should the computer be? Pick 1 (gentle) to {} (its best).",
+        STRENGTH_LEVELS
+    );
+    let strength = read_menu_number(&[strength_prompt.as_str()], STRENGTH_LEVELS);
+
+    ComputerPlayer {
+        color: human_color.get_inverse_color(),
+        limits: search_limits_for_strength(strength),
+    }
+}
+
+pub fn handle_fen_import() {
+    loop {
+        alienify_output_text("Please input a path for a fen file:");
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        let trimmed_indication = indication.trim(); //need to remove the newline that will occur on input.
+
+        match ingest_fen_file(trimmed_indication) {
+            Ok(board) => {
+                println!("the board exists");
+                loop {
+                    alienify_output_text("Would you like to play a game with this board?");
+                    alienify_output_text("1: Start playing");
+                    alienify_output_text("2: Exit");
+
+                    let mut selection = String::new();
+
+                    stdin()
+                        .read_line(&mut selection)
+                        .expect("Failed to read line.");
+
+                    let selection_number: u32 = match selection.trim().parse() {
+                        Ok(num) => num,
+                        Err(_) => {
+                            println!("It appears you entered something that wasn't a positive integer. Oops!");
+                            continue;
+                        }
+                    };
+
+                    match selection_number {
+                        1 => {
+                            let mut game = Game::from_board(board);
+                            game.set_tag("Date", &pgn_date_today());
+                            play_chess(game, None);
+                            break;
+                        }
+                        2 => {
+                            break;
+                        }
+                        _ => {
+                            alienify_output_text("Oops! That number wasn't one of the options.");
+                        }
+                    }
+                }
+                break;
+            }
+            Err(fen_error) => {
+                println!("Oops! {}", fen_error);
+            }
+        }
+    }
+}
+
+pub fn handle_pgn_import() {
+    alienify_output_text("Please input a path for a pgn file:");
+    let mut indication = String::new();
+
+    stdin()
+        .read_line(&mut indication)
+        .expect("Failed to read line");
+
+    let pgn_reader = match read_pgn_file(indication.trim()) {
+        Ok(pgn_reader) => pgn_reader,
+        Err(_) => {
+            println!("Oops! I couldn't open {}", indication.trim());
+            return;
+        }
+    };
+
+    //games are read one at a time, so we can offer each one up without loading the whole file.
+    for (game_index, game_result) in pgn_reader.enumerate() {
+        let game = match game_result {
+            Ok(game) => game,
+            Err(pgn_error) => {
+                println!(
+                    "Game {} couldn't be read, {}. Skipping it.",
+                    game_index + 1,
+                    pgn_error
+                );
+                continue;
+            }
+        };
+
+        let game_summary = format!(
+            "Game {}: {} vs {} ({}), {} moves",
+            game_index + 1,
+            game.get_tag("White").unwrap_or("?"),
+            game.get_tag("Black").unwrap_or("?"),
+            game.get_tag("Result").unwrap_or("*"),
+            game.moves().len()
+        );
+        alienify_output_text(&game_summary);
+        alienify_board(game.current_board());
+
+        loop {
+            alienify_output_text("1: Keep playing from here");
+            alienify_output_text("2: Show the next game");
+            alienify_output_text("3: Exit");
+
+            let mut selection = String::new();
+
+            stdin()
+                .read_line(&mut selection)
+                .expect("Failed to read line.");
+
+            match selection.trim().parse::<u32>() {
+                Ok(1) => {
+                    play_chess(game, None);
+                    return;
+                }
+                Ok(2) => break,
+                Ok(3) => return,
+                _ => alienify_output_text("Oops! That number wasn't one of the options."),
+            }
+        }
+    }
+
+    alienify_output_text("That's every game in the file.");
+}
+
+pub fn save_game(game: &Game) {
+    loop {
+        alienify_output_text("How would you like to save this game?");
+        alienify_output_text("1: FEN (just the current position)");
+        alienify_output_text("2: PGN (the whole game)");
+
+        let mut selection = String::new();
+
+        stdin()
+            .read_line(&mut selection)
+            .expect("Failed to read line");
+
+        match selection.trim().parse::<u32>() {
+            Ok(1) => {
+                save_to_fen_file(game.current_board().clone());
+                break;
+            }
+            Ok(2) => {
+                save_to_pgn_file(game);
+                break;
+            }
+            _ => alienify_output_text("Oops! That wasn't one of the options."),
+        }
+    }
+}
+
+fn read_new_file_path() -> String {
+    loop {
+        alienify_output_text("Please enter a name of the file you would like to save the game to:");
+        let mut indication = String::new();
+
+        stdin()
+            .read_line(&mut indication)
+            .expect("Failed to read line");
+
+        let trimmed_indication = indication.trim();
+
+        if path_exists(trimmed_indication) {
+            println!(
+                "Hey, that file already exists! I can't have you deleting files that already exist!"
+            );
+        } else {
+            return trimmed_indication.to_string();
+        }
+    }
+}
+
+pub fn save_to_pgn_file(game: &Game) {
+    let mut game_record = game.clone();
+    for (tag_name, player_prompt) in [
+        (
+            "White",
+            "Who played white? Leave this blank if you'd rather not say.",
+        ),
+        (
+            "Black",
+            "Who played black? Leave this blank if you'd rather not say.",
+        ),
+    ] {
+        alienify_output_text(player_prompt);
+        let mut player_name = String::new();
+
+        stdin()
+            .read_line(&mut player_name)
+            .expect("Failed to read line");
+
+        if !player_name.trim().is_empty() {
+            game_record.set_tag(tag_name, player_name.trim());
+        }
+    }
+
+    let file_path = read_new_file_path();
+    let mut pgn_file = File::create(file_path).expect("creation failed");
+
+    pgn_file
+        .write_all(game_record.to_pgn().as_bytes())
+        .expect("write failed");
+
+    println!("Successfully saved your game!");
+}
+
+pub fn save_to_fen_file(board: Board) {
+    let file_path = read_new_file_path();
+    let mut fen_file = File::create(file_path).expect("creation failed");
+
+    // Write contents to the file
+    fen_file
+        .write(board.generate_fen_string().as_bytes())
+        .expect("write failed");
+
+    println!("Successfully saved your game!");
+}
@@ -0,0 +1,333 @@
+use std::sync::OnceLock;
+
+use crate::{
+    board::{ColumnLetter, Coordinates},
+    pieces::PieceColor,
+};
+
+//a bitboard is one bit per square. Bit 0 is a1, bit 7 is h1, bit 56 is a8 and bit 63 is h8.
+pub type Bitboard = u64;
+
+pub const EMPTY_BITBOARD: Bitboard = 0;
+
+//b1, a2 and every square of the same color as them. a1 is a dark square.
+pub const LIGHT_SQUARES: Bitboard = 0x55AA_55AA_55AA_55AA;
+
+pub fn square_index(coords: &Coordinates) -> Option<usize> {
+    if coords.number < 1 || coords.number > 8 {
+        None
+    } else {
+        Some(((coords.number - 1) * 8 + coords.letter.eval()) as usize)
+    }
+}
+
+pub fn coordinates_from_index(index: usize) -> Coordinates {
+    match ColumnLetter::construct_letter_from_isize((index % 8) as isize) {
+        Ok(letter) => Coordinates {
+            letter,
+            number: (index / 8) as isize + 1,
+        },
+        Err(_) => {
+            panic!("a square index somehow produced a column outside the board")
+        }
+    }
+}
+
+pub fn square_bit(index: usize) -> Bitboard {
+    1u64 << index
+}
+
+pub fn coordinates_bit(coords: &Coordinates) -> Bitboard {
+    match square_index(coords) {
+        Some(index) => square_bit(index),
+        None => EMPTY_BITBOARD,
+    }
+}
+
+//walks the set bits of a bitboard from a1 up to h8, handing back each square index.
+pub struct SquareIndices {
+    remaining: Bitboard,
+}
+
+impl Iterator for SquareIndices {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let index = self.remaining.trailing_zeros() as usize;
+            self.remaining &= self.remaining - 1;
+            Some(index)
+        }
+    }
+}
+
+pub fn square_indices(bitboard: Bitboard) -> SquareIndices {
+    SquareIndices {
+        remaining: bitboard,
+    }
+}
+
+const fn offset_target(index: usize, file_step: isize, rank_step: isize) -> Bitboard {
+    let file = (index % 8) as isize + file_step;
+    let rank = (index / 8) as isize + rank_step;
+    if file < 0 || file > 7 || rank < 0 || rank > 7 {
+        0
+    } else {
+        1u64 << (rank * 8 + file)
+    }
+}
+
+const fn generate_step_attacks(steps: [(isize, isize); 8]) -> [Bitboard; 64] {
+    let mut attacks = [0u64; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut step = 0;
+        while step < 8 {
+            attacks[index] |= offset_target(index, steps[step].0, steps[step].1);
+            step += 1;
+        }
+        index += 1;
+    }
+    attacks
+}
+
+const fn generate_pawn_attacks(rank_step: isize) -> [Bitboard; 64] {
+    let mut attacks = [0u64; 64];
+    let mut index = 0;
+    while index < 64 {
+        attacks[index] = offset_target(index, -1, rank_step) | offset_target(index, 1, rank_step);
+        index += 1;
+    }
+    attacks
+}
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = generate_step_attacks([
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+]);
+
+const KING_ATTACKS: [Bitboard; 64] = generate_step_attacks([
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+]);
+
+const WHITE_PAWN_ATTACKS: [Bitboard; 64] = generate_pawn_attacks(1);
+const BLACK_PAWN_ATTACKS: [Bitboard; 64] = generate_pawn_attacks(-1);
+
+const ROOK_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP_DIRECTIONS: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+pub fn knight_attacks(index: usize) -> Bitboard {
+    KNIGHT_ATTACKS[index]
+}
+
+pub fn king_attacks(index: usize) -> Bitboard {
+    KING_ATTACKS[index]
+}
+
+//the squares a pawn of this color standing on index could take on.
+pub fn pawn_attacks(index: usize, pawn_color: PieceColor) -> Bitboard {
+    match pawn_color {
+        PieceColor::White => WHITE_PAWN_ATTACKS[index],
+        PieceColor::Black => BLACK_PAWN_ATTACKS[index],
+    }
+}
+
+//the slow way of working out a slider's attacks. We only use it to build the magic tables.
+fn ray_attacks(index: usize, occupied: Bitboard, directions: &[(isize, isize); 4]) -> Bitboard {
+    let mut attacks = EMPTY_BITBOARD;
+    for (file_step, rank_step) in directions.iter() {
+        let mut file = (index % 8) as isize;
+        let mut rank = (index / 8) as isize;
+        loop {
+            file += file_step;
+            rank += rank_step;
+            if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+                break;
+            }
+            let target_bit = square_bit((rank * 8 + file) as usize);
+            attacks |= target_bit;
+            if occupied & target_bit != 0 {
+                break;
+            }
+        }
+    }
+    attacks
+}
+
+//the squares whose occupancy matters for a slider. The last square in each direction never blocks anything past it.
+fn relevant_occupancy_mask(index: usize, directions: &[(isize, isize); 4]) -> Bitboard {
+    let mut mask = EMPTY_BITBOARD;
+    for (file_step, rank_step) in directions.iter() {
+        let mut file = (index % 8) as isize + file_step;
+        let mut rank = (index / 8) as isize + rank_step;
+        while (0..8).contains(&(file + file_step)) && (0..8).contains(&(rank + rank_step)) {
+            mask |= square_bit((rank * 8 + file) as usize);
+            file += file_step;
+            rank += rank_step;
+        }
+    }
+    mask
+}
+
+struct Magic {
+    mask: Bitboard,
+    multiplier: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn table_index(&self, occupied: Bitboard) -> usize {
+        self.offset + ((occupied & self.mask).wrapping_mul(self.multiplier) >> self.shift) as usize
+    }
+}
+
+struct SlidingAttacks {
+    rook_magics: Vec<Magic>,
+    bishop_magics: Vec<Magic>,
+    attack_table: Vec<Bitboard>,
+}
+
+//xorshift64star. We want the same magics on every run, so the seeds are fixed.
+struct PseudoRandom {
+    state: u64,
+}
+
+impl PseudoRandom {
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(2685821657736338717)
+    }
+
+    fn sparse(&mut self) -> u64 {
+        //magics with only a few bits set are found much faster.
+        self.next() & self.next() & self.next()
+    }
+}
+
+const MAGIC_SEEDS: [u64; 8] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+fn find_magics(directions: &[(isize, isize); 4], attack_table: &mut Vec<Bitboard>) -> Vec<Magic> {
+    let mut magics: Vec<Magic> = Vec::new();
+    let mut occupancies: Vec<Bitboard> = Vec::new();
+    let mut references: Vec<Bitboard> = Vec::new();
+    let mut epochs: Vec<usize> = Vec::new();
+    let mut current_epoch = 0;
+
+    for index in 0..64 {
+        let mask = relevant_occupancy_mask(index, directions);
+        let relevant_bits = mask.count_ones();
+        let table_size = 1usize << relevant_bits;
+
+        //go through every subset of the mask with the carry rippler trick.
+        occupancies.clear();
+        references.clear();
+        let mut subset = EMPTY_BITBOARD;
+        loop {
+            occupancies.push(subset);
+            references.push(ray_attacks(index, subset, directions));
+            subset = subset.wrapping_sub(mask) & mask;
+            if subset == EMPTY_BITBOARD {
+                break;
+            }
+        }
+
+        let offset = attack_table.len();
+        attack_table.resize(offset + table_size, EMPTY_BITBOARD);
+        epochs.clear();
+        epochs.resize(table_size, 0);
+
+        let mut random = PseudoRandom {
+            state: MAGIC_SEEDS[index / 8],
+        };
+        let mut magic = Magic {
+            mask,
+            multiplier: 0,
+            shift: 64 - relevant_bits,
+            offset,
+        };
+
+        'searching: loop {
+            magic.multiplier = random.sparse();
+            if (mask.wrapping_mul(magic.multiplier) >> 56).count_ones() < 6 {
+                continue;
+            }
+
+            //epochs let us reuse the table between attempts without clearing it.
+            current_epoch += 1;
+            for (occupancy, reference) in occupancies.iter().zip(references.iter()) {
+                let table_index = magic.table_index(*occupancy);
+                let local_index = table_index - offset;
+                if epochs[local_index] < current_epoch {
+                    epochs[local_index] = current_epoch;
+                    attack_table[table_index] = *reference;
+                } else if attack_table[table_index] != *reference {
+                    continue 'searching;
+                }
+            }
+            break;
+        }
+
+        magics.push(magic);
+    }
+
+    magics
+}
+
+fn sliding_attacks() -> &'static SlidingAttacks {
+    static SLIDING_ATTACKS: OnceLock<SlidingAttacks> = OnceLock::new();
+    SLIDING_ATTACKS.get_or_init(|| {
+        let mut attack_table: Vec<Bitboard> = Vec::new();
+        let rook_magics = find_magics(&ROOK_DIRECTIONS, &mut attack_table);
+        let bishop_magics = find_magics(&BISHOP_DIRECTIONS, &mut attack_table);
+        SlidingAttacks {
+            rook_magics,
+            bishop_magics,
+            attack_table,
+        }
+    })
+}
+
+pub fn rook_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_attacks();
+    tables.attack_table[tables.rook_magics[index].table_index(occupied)]
+}
+
+pub fn bishop_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_attacks();
+    tables.attack_table[tables.bishop_magics[index].table_index(occupied)]
+}
+
+pub fn queen_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+    rook_attacks(index, occupied) | bishop_attacks(index, occupied)
+}
+
+//the squares strictly between two squares on the same line, or nothing if they don't share one.
+pub fn squares_between(from_index: usize, to_index: usize) -> Bitboard {
+    let from_bit = square_bit(from_index);
+    let to_bit = square_bit(to_index);
+    if rook_attacks(from_index, EMPTY_BITBOARD) & to_bit != 0 {
+        rook_attacks(from_index, to_bit) & rook_attacks(to_index, from_bit)
+    } else if bishop_attacks(from_index, EMPTY_BITBOARD) & to_bit != 0 {
+        bishop_attacks(from_index, to_bit) & bishop_attacks(to_index, from_bit)
+    } else {
+        EMPTY_BITBOARD
+    }
+}
@@ -1,11 +1,20 @@
 use core::fmt;
-use std::{slice::Iter, thread, time};
+use std::slice::Iter;
 
 use crate::{
+    bitboard::*,
+    moves::Move,
     pieces::*,
-    rules::{king_checkmate_state, parse_move_legality, MateState},
+    rules::{
+        insufficient_mating_material, king_checkmate_state, parse_move_legality, probing_promotion,
+        promotion_required, MateState,
+    },
+    zobrist::{black_to_move_key, castling_key, passant_key, piece_key},
 };
 
+//Chess960 start positions are numbered from 0 up to this, and number 518 is the standard setup.
+pub const CHESS960_POSITIONS: u32 = 960;
+
 pub fn isize_difference(a: isize, b: isize) -> isize {
     isize::abs(a - b)
 }
@@ -25,7 +34,7 @@ pub fn board_safe_isize_converter(size: isize) -> Result<usize, &'static str> {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ColumnLetter {
     A,
     B,
@@ -112,8 +121,8 @@ impl DiagonalDirection {
         match self {
             Self::DownLeft => letter_value > 0 && number_value > 1,
             Self::DownRight => letter_value < 7 && number_value > 1,
-            Self::UpLeft => letter_value > 0 && number_value < 7,
-            Self::UpRight => letter_value < 7 && number_value < 7,
+            Self::UpLeft => letter_value > 0 && number_value < 8,
+            Self::UpRight => letter_value < 7 && number_value < 8,
         }
     }
 
@@ -180,7 +189,7 @@ pub struct SquareToSquareInformation {
     pub distance: isize,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Coordinates {
     pub letter: ColumnLetter,
     pub number: isize,
@@ -196,15 +205,14 @@ impl Coordinates {
 }
 
 impl fmt::Display for Coordinates {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        print!("{}{}", self.letter, self.number);
-        Ok(())
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.letter, self.number)
     }
 }
 
 pub fn measure_distance(from: &Coordinates, to: &Coordinates) -> SquareToSquareInformation {
     let mut garnered_move_direction: MoveDirection = MoveDirection::NoMove;
-    if from.number < 1 || from.number > 8 || to.number < 0 || to.number > 8 {
+    if from.number < 1 || from.number > 8 || to.number < 1 || to.number > 8 {
         return SquareToSquareInformation {
             move_direction: garnered_move_direction,
             distance: 0,
@@ -283,10 +291,11 @@ impl Square {
                         board,
                         legal_target_squares,
                     );
+                    //columns count from 0, but rows count from 1
                     self.get_legal_single_target(
                         coordinates,
                         coordinates.letter,
-                        i,
+                        i + 1,
                         board,
                         legal_target_squares,
                     );
@@ -313,13 +322,13 @@ impl Square {
             moving_letter_value,
             moving_number_value,
         ) {
+            DiagonalDirection::modify_letter_and_number_values(
+                &direction,
+                &mut moving_letter_value,
+                &mut moving_number_value,
+            );
             match ColumnLetter::construct_letter_from_isize(moving_letter_value) {
                 Ok(found_letter) => {
-                    DiagonalDirection::modify_letter_and_number_values(
-                        &direction,
-                        &mut moving_letter_value,
-                        &mut moving_number_value,
-                    );
                     self.get_legal_single_target(
                         coordinates,
                         found_letter,
@@ -382,8 +391,12 @@ impl Square {
             letter: col_letter,
             number: row_number,
         };
-        let (move_legal, _, _, _, _, _, _, _) =
-            parse_move_legality(from, &investigating_coordinates, board);
+        let (move_legal, _, _, _, _, _, _, _) = parse_move_legality(
+            from,
+            &investigating_coordinates,
+            probing_promotion(from, &investigating_coordinates, board),
+            board,
+        );
         if move_legal {
             legal_target_squares.push(investigating_coordinates);
         }
@@ -407,7 +420,7 @@ impl Square {
                             }
                             PieceColor::White => {
                                 single_step = 1;
-                                double_step = -2;
+                                double_step = 2;
                             }
                         };
 
@@ -528,6 +541,18 @@ impl Square {
                                 }
                             }
                         }
+
+                        //castling is the only time the king goes further than next door. Where it's written
+                        //as going depends on where the rooks started, so the board works that out. A Chess960
+                        //king taking a rook right beside it already turned up above.
+                        for is_kingside in [true, false] {
+                            let castling_target = board.castling_move(piece.color, is_kingside).to;
+                            if board.king_can_castle(piece.color, is_kingside)
+                                && !legal_target_squares.contains(&castling_target)
+                            {
+                                legal_target_squares.push(castling_target);
+                            }
+                        }
                     }
                 }
             }
@@ -570,9 +595,9 @@ pub struct Row {
 }
 
 impl fmt::Display for Row {
-    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for square in self.squares.iter() {
-            print!("{}", square);
+            write!(f, "{}", square)?;
         }
         Ok(())
     }
@@ -639,6 +664,66 @@ impl Row {
         }
     }
 
+    pub fn chess960_back_row(
+        piece_color: PieceColor,
+        position_number: u32,
+    ) -> Result<Self, &'static str> {
+        if position_number >= CHESS960_POSITIONS {
+            return Err("Chess960 positions are numbered from 0 to 959");
+        }
+
+        //the number is read like a mixed radix number. The light square bishop goes down first, then the dark one,
+        //then the queen and the knights fill gaps in turn, and the last three gaps get rook, king, rook.
+        let mut kinds: [Option<PieceKind>; 8] = [None; 8];
+        let mut remaining = position_number as usize;
+        kinds[(remaining % 4) * 2 + 1] = Some(PieceKind::Bishop);
+        remaining /= 4;
+        kinds[(remaining % 4) * 2] = Some(PieceKind::Bishop);
+        remaining /= 4;
+        let queen_gap = remaining % 6;
+        remaining /= 6;
+        let (first_knight_gap, second_knight_gap) = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ][remaining];
+
+        let gaps = |kinds: &[Option<PieceKind>; 8]| {
+            (0..8)
+                .filter(|index| kinds[*index].is_none())
+                .collect::<Vec<usize>>()
+        };
+        let queen_gaps = gaps(&kinds);
+        kinds[queen_gaps[queen_gap]] = Some(PieceKind::Queen);
+        let knight_gaps = gaps(&kinds);
+        kinds[knight_gaps[first_knight_gap]] = Some(PieceKind::Knight);
+        kinds[knight_gaps[second_knight_gap]] = Some(PieceKind::Knight);
+        for (gap, kind) in
+            gaps(&kinds)
+                .into_iter()
+                .zip([PieceKind::Rook, PieceKind::King, PieceKind::Rook])
+        {
+            kinds[gap] = Some(kind);
+        }
+
+        Ok(Row {
+            squares: kinds.map(|opt_kind| match opt_kind {
+                Some(kind) => Square::Full(Piece {
+                    color: piece_color,
+                    kind,
+                }),
+                None => Square::Empty,
+            }),
+        })
+    }
+
     pub fn set_square(&mut self, index: usize, square: Square) {
         self.squares[index] = square;
     }
@@ -668,29 +753,34 @@ impl Row {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct SideInformation {
-    taken_pieces: Vec<PieceKind>,
+    //how many of each kind of piece this side has taken, indexed by PieceKind::index. Counts instead of a list so copying a side never allocates.
+    taken_pieces: [i32; 6],
     can_castle_kingside: bool,
     can_castle_queenside: bool,
+    //the files the castling rooks started on. Always H and A in standard chess, anywhere either side of the king in Chess960.
+    kingside_rook_letter: ColumnLetter,
+    queenside_rook_letter: ColumnLetter,
     current_king_square: Coordinates,
 }
 
 impl fmt::Display for SideInformation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "kingside castling: {:?}\n", self.can_castle_kingside);
-        write!(f, "queenside castling: {:?}\n", self.can_castle_queenside);
-        println!("king location: {}", self.current_king_square);
-        Ok(())
+        writeln!(f, "kingside castling: {:?}", self.can_castle_kingside)?;
+        writeln!(f, "queenside castling: {:?}", self.can_castle_queenside)?;
+        write!(f, "king location: {}", self.current_king_square)
     }
 }
 
 impl SideInformation {
     pub fn default(king_color: PieceColor) -> Self {
         SideInformation {
-            taken_pieces: Vec::new(),
+            taken_pieces: [0; 6],
             can_castle_kingside: true,
             can_castle_queenside: true,
+            kingside_rook_letter: ColumnLetter::H,
+            queenside_rook_letter: ColumnLetter::A,
             current_king_square: Coordinates {
                 letter: ColumnLetter::E,
                 number: match king_color {
@@ -709,114 +799,78 @@ impl SideInformation {
         taken_bishops: i32,
         taken_queens: i32,
     ) {
-        self.taken_pieces.clear();
-
-        for _ in 0..taken_pawns {
-            self.taken_pieces.push(PieceKind::Pawn);
-        }
-
-        for _ in 0..taken_rooks {
-            self.taken_pieces.push(PieceKind::Rook);
-        }
-
-        for _ in 0..taken_knights {
-            self.taken_pieces.push(PieceKind::Knight);
-        }
-
-        for _ in 0..taken_bishops {
-            self.taken_pieces.push(PieceKind::Bishop);
-        }
-
-        for _ in 0..taken_queens {
-            self.taken_pieces.push(PieceKind::Queen);
-        }
+        self.taken_pieces = [0; 6];
+        self.taken_pieces[PieceKind::Pawn.index()] = taken_pawns;
+        self.taken_pieces[PieceKind::Rook.index()] = taken_rooks;
+        self.taken_pieces[PieceKind::Knight.index()] = taken_knights;
+        self.taken_pieces[PieceKind::Bishop.index()] = taken_bishops;
+        self.taken_pieces[PieceKind::Queen.index()] = taken_queens;
     }
 
-    pub fn sort_taken_pieces(&mut self) {
-        let mut pawn_count = 0;
-        let mut rook_count = 0;
-        let mut knight_count = 0;
-        let mut bishop_count = 0;
-        let mut queen_count = 0;
-
-        for piece in self.taken_pieces.iter() {
-            match piece {
-                PieceKind::Pawn => {
-                    pawn_count += 1;
-                }
-                PieceKind::Rook => {
-                    rook_count += 1;
-                }
-                PieceKind::Knight => {
-                    knight_count += 1;
-                }
-                PieceKind::Bishop => {
-                    bishop_count += 1;
-                }
-                PieceKind::Queen => {
-                    queen_count += 1;
-                }
-                PieceKind::King => {
-                    panic!("Oops! A king somehow made its way into your taken pieces!");
-                }
-            }
-        }
-
-        self.taken_pieces.clear();
-
-        for _ in 0..pawn_count {
-            self.taken_pieces.push(PieceKind::Pawn);
-        }
-
-        for _ in 0..rook_count {
-            self.taken_pieces.push(PieceKind::Rook);
-        }
-
-        for _ in 0..knight_count {
-            self.taken_pieces.push(PieceKind::Knight);
-        }
-
-        for _ in 0..bishop_count {
-            self.taken_pieces.push(PieceKind::Bishop);
-        }
-
-        for _ in 0..queen_count {
-            self.taken_pieces.push(PieceKind::Queen);
-        }
+    pub fn taken_piece_count(&self, piece_kind: PieceKind) -> i32 {
+        self.taken_pieces[piece_kind.index()]
     }
 
-    pub fn generate_fen_string(&self) -> String {
-        let mut accum_string = String::new();
-
-        if self.can_castle_kingside {
-            accum_string.push('k');
-        }
+    pub fn set_castling_rights(&mut self, kingside: bool, queenside: bool) {
+        self.can_castle_kingside = kingside;
+        self.can_castle_queenside = queenside;
+    }
 
-        if self.can_castle_queenside {
-            accum_string.push('q');
+    pub fn castling_rook_letter(&self, is_kingside: bool) -> ColumnLetter {
+        if is_kingside {
+            self.kingside_rook_letter
+        } else {
+            self.queenside_rook_letter
         }
-
-        accum_string
     }
 
-    pub fn set_castling_rights(&mut self, kingside: bool, queenside: bool) {
-        self.can_castle_kingside = kingside;
-        self.can_castle_queenside = queenside;
+    //gives the side the right to castle with the rook on this file.
+    pub fn add_castling_rook(&mut self, is_kingside: bool, rook_letter: ColumnLetter) {
+        if is_kingside {
+            self.can_castle_kingside = true;
+            self.kingside_rook_letter = rook_letter;
+        } else {
+            self.can_castle_queenside = true;
+            self.queenside_rook_letter = rook_letter;
+        }
     }
 
     pub fn add_taken_piece(&mut self, piece_kind: PieceKind) {
-        self.taken_pieces.push(piece_kind);
-        self.sort_taken_pieces();
+        if piece_kind == PieceKind::King {
+            panic!("Oops! A king somehow made its way into your taken pieces!");
+        }
+        self.taken_pieces[piece_kind.index()] += 1;
     }
 
     pub fn total_taken_pieces(&self) -> u32 {
         let mut total_value = 0;
-        for piece in self.taken_pieces.iter() {
-            total_value += piece.get_value()
+        for piece_kind in PieceKind::iterator() {
+            total_value += piece_kind.get_value() * self.taken_piece_count(*piece_kind) as u32;
         }
         total_value
     }
 
+    pub fn write_taken_pieces(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //taken pieces have always been shown pawns first, then rooks, knights, bishops and queens.
+        for piece_kind in [
+            PieceKind::Pawn,
+            PieceKind::Rook,
+            PieceKind::Knight,
+            PieceKind::Bishop,
+            PieceKind::Queen,
+        ] {
+            for _ in 0..self.taken_piece_count(piece_kind) {
+                write!(f, "{} ", piece_kind)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_king_square(&mut self, coords: Coordinates) {
+        //unlike update_king_location this is bookkeeping rather than a move, so castling rights are left alone.
+        self.current_king_square = coords;
+    }
+
     pub fn update_king_location(&mut self, letter: &ColumnLetter, number: &isize) {
         self.current_king_square = Coordinates {
             letter: *letter,
@@ -855,6 +909,9 @@ pub enum MoveResult {
     WrongTurn,
     MoveIllegal,
     EmptySquare,
+    FivefoldRepetition,
+    InsufficientMaterial,
+    SeventyFiveMoveRule,
 }
 
 impl fmt::Display for MoveResult {
@@ -863,21 +920,59 @@ impl fmt::Display for MoveResult {
     }
 }
 
+//a piece stuck between its own king and the slider pinning it. The ray holds the squares it can still move to,
+//which run up to and include the pinning piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pin {
+    pub pinned: Coordinates,
+    pub pinner: Coordinates,
+    pub ray: Bitboard,
+}
+
+//where the king and rook start and finish when castling, as square indices. In Chess960 these can overlap, so the
+//king might not move at all, or land where its rook started.
+#[derive(Clone, Copy)]
+struct CastlingSquares {
+    king_from: usize,
+    king_to: usize,
+    rook_from: usize,
+    rook_to: usize,
+}
+
+//everything make_move changes that can't be worked out again from the move itself. Hand it back to unmake_move to step back.
+#[derive(Clone, Copy)]
+pub struct Undo {
+    made_move: Move,
+    moved_piece: Piece,
+    opt_captured: Option<(usize, Piece)>,
+    opt_castling: Option<CastlingSquares>,
+    previous_passant_square: Option<Coordinates>,
+    previous_white_side: SideInformation,
+    previous_black_side: SideInformation,
+    previous_half_turns: u32,
+    previous_full_turns: u32,
+    previous_hash: u64,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Board {
-    rows: [Row; 8],
+    //one bitboard per kind of piece, and one per color. A piece sits where its kind and color overlap.
+    kind_bitboards: [Bitboard; 6],
+    color_bitboards: [Bitboard; 2],
     turn: PieceColor,
     opt_legal_passant_square: Option<Coordinates>,
     white_side_information: SideInformation,
     black_side_information: SideInformation,
     half_turns: u32,
     full_turns: u32,
+    //the zobrist key for this position, kept up to date as pieces and state change.
+    hash: u64,
 }
 
 impl Board {
     pub fn default() -> Self {
-        Board {
-            rows: [
+        Board::new(
+            [
                 Row::default_back_row(PieceColor::Black),
                 Row::pawn_row(PieceColor::Black),
                 Row::default(),
@@ -887,13 +982,48 @@ impl Board {
                 Row::pawn_row(PieceColor::White),
                 Row::default_back_row(PieceColor::White),
             ],
-            turn: PieceColor::White,
-            opt_legal_passant_square: None,
-            white_side_information: SideInformation::default(PieceColor::White),
-            black_side_information: SideInformation::default(PieceColor::Black),
-            half_turns: 0,
-            full_turns: 1,
+            PieceColor::White,
+            None,
+            SideInformation::default(PieceColor::White),
+            SideInformation::default(PieceColor::Black),
+            0,
+            1,
+        )
+    }
+
+    pub fn chess960(position_number: u32) -> Result<Self, &'static str> {
+        let mut board = Board::new(
+            [
+                Row::chess960_back_row(PieceColor::Black, position_number)?,
+                Row::pawn_row(PieceColor::Black),
+                Row::default(),
+                Row::default(),
+                Row::default(),
+                Row::default(),
+                Row::pawn_row(PieceColor::White),
+                Row::chess960_back_row(PieceColor::White, position_number)?,
+            ],
+            PieceColor::White,
+            None,
+            SideInformation::default(PieceColor::White),
+            SideInformation::default(PieceColor::Black),
+            0,
+            1,
+        );
+        board.locate_kings();
+
+        //both sides start with both rights, so only the rook files change. Those aren't part of the hash.
+        for color in PieceColor::iterator() {
+            for is_kingside in [true, false] {
+                if let Some(rook_letter) = board.outermost_rook_letter(*color, is_kingside) {
+                    board
+                        .side_information_mut(*color)
+                        .add_castling_rook(is_kingside, rook_letter);
+                }
+            }
         }
+
+        Ok(board)
     }
 
     pub fn new(
@@ -905,21 +1035,164 @@ impl Board {
         input_half_turns: u32,
         input_full_turns: u32,
     ) -> Board {
-        Board {
-            rows: input_rows,
+        let mut board = Board {
+            kind_bitboards: [EMPTY_BITBOARD; 6],
+            color_bitboards: [EMPTY_BITBOARD; 2],
             turn: current_turn,
             opt_legal_passant_square: input_passant_square,
             white_side_information: input_white_side,
             black_side_information: input_black_side,
             half_turns: input_half_turns,
             full_turns: input_full_turns,
+            hash: 0,
+        };
+
+        //rows are listed from the 8th row down, the same way a fen string lists them.
+        for (row_index, row) in input_rows.iter().enumerate() {
+            for (letter_index, square) in row.squares.iter().enumerate() {
+                board.place_square(((7 - row_index) * 8) + letter_index, *square);
+            }
+        }
+        board.hash ^= board.state_hash();
+
+        board
+    }
+
+    pub fn hash_key(&self) -> u64 {
+        self.hash
+    }
+
+    fn state_hash(&self) -> u64 {
+        //everything in the hash that isn't a piece on a square.
+        let mut state_hash = 0;
+        if self.turn == PieceColor::Black {
+            state_hash ^= black_to_move_key();
+        }
+        for (castling_index, can_castle) in [
+            self.white_side_information.king_can_castle(true),
+            self.white_side_information.king_can_castle(false),
+            self.black_side_information.king_can_castle(true),
+            self.black_side_information.king_can_castle(false),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if *can_castle {
+                state_hash ^= castling_key(castling_index);
+            }
+        }
+        //the en passant file only counts when the capture can really be played, the same as for repetition.
+        if let Some(passant_square) = self.opt_legal_passant_square {
+            if let Some(passant_index) = square_index(&passant_square) {
+                if self.passant_capture_available(passant_index) {
+                    state_hash ^= passant_key(passant_index % 8);
+                }
+            }
+        }
+        state_hash
+    }
+
+    fn passant_capture_available(&self, passant_index: usize) -> bool {
+        //a pawn standing by isn't enough. It could be pinned, or taking could open a line onto its own king.
+        let own_pawn = Piece {
+            color: self.turn,
+            kind: PieceKind::Pawn,
+        };
+        let capturing_pawns = pawn_attacks(passant_index, self.turn.get_inverse_color())
+            & self.piece_bitboard(own_pawn);
+        let king_bitboard = self.piece_bitboard(Piece {
+            color: self.turn,
+            kind: PieceKind::King,
+        });
+        let king_index = match square_indices(king_bitboard).next() {
+            Some(king_index) => king_index,
+            None => return capturing_pawns != EMPTY_BITBOARD,
+        };
+        //the pawn that stepped two squares stands just past the en passant square.
+        let stepped_index = match self.turn {
+            PieceColor::White => passant_index - 8,
+            PieceColor::Black => passant_index + 8,
+        };
+        square_indices(capturing_pawns).any(|pawn_index| {
+            let mut copied_board = self.clone();
+            copied_board.place_square(pawn_index, Square::Empty);
+            copied_board.place_square(stepped_index, Square::Empty);
+            copied_board.place_square(passant_index, Square::Full(own_pawn));
+            !copied_board.square_attacked_by(king_index, self.turn.get_inverse_color())
+        })
+    }
+
+    pub fn get_rows(&self) -> [Row; 8] {
+        let mut rows = [Row::default(); 8];
+        for (row_index, row) in rows.iter_mut().enumerate() {
+            for letter_index in 0..8 {
+                row.squares[letter_index] =
+                    self.square_at_index((7 - row_index) * 8 + letter_index);
+            }
+        }
+        rows
+    }
+
+    pub fn piece_bitboard(&self, piece: Piece) -> Bitboard {
+        self.kind_bitboards[piece.kind.index()] & self.color_bitboards[piece.color.index()]
+    }
+
+    pub fn kind_bitboard(&self, kind: PieceKind) -> Bitboard {
+        self.kind_bitboards[kind.index()]
+    }
+
+    pub fn color_bitboard(&self, color: PieceColor) -> Bitboard {
+        self.color_bitboards[color.index()]
+    }
+
+    pub fn occupied_bitboard(&self) -> Bitboard {
+        self.color_bitboards[0] | self.color_bitboards[1]
+    }
+
+    fn square_at_index(&self, index: usize) -> Square {
+        let index_bit = square_bit(index);
+        let opt_color = PieceColor::iterator()
+            .find(|color| self.color_bitboards[color.index()] & index_bit != 0);
+        match opt_color {
+            Some(color) => {
+                match PieceKind::iterator()
+                    .find(|kind| self.kind_bitboards[kind.index()] & index_bit != 0)
+                {
+                    Some(kind) => Square::Full(Piece {
+                        color: *color,
+                        kind: *kind,
+                    }),
+                    None => {
+                        panic!("a square had a color but no kind of piece on it")
+                    }
+                }
+            }
+            None => Square::Empty,
+        }
+    }
+
+    fn place_square(&mut self, index: usize, square: Square) {
+        if let Square::Full(old_piece) = self.square_at_index(index) {
+            self.hash ^= piece_key(old_piece, index);
+        }
+        let cleared_bits = !square_bit(index);
+        for bitboard in self.kind_bitboards.iter_mut() {
+            *bitboard &= cleared_bits;
+        }
+        for bitboard in self.color_bitboards.iter_mut() {
+            *bitboard &= cleared_bits;
+        }
+        if let Square::Full(piece) = square {
+            self.kind_bitboards[piece.kind.index()] |= square_bit(index);
+            self.color_bitboards[piece.color.index()] |= square_bit(index);
+            self.hash ^= piece_key(piece, index);
         }
     }
 
     pub fn generate_fen_string(&self) -> String {
         let mut fen_output = String::new();
 
-        for (index, row) in self.rows.iter().enumerate() {
+        for (index, row) in self.get_rows().iter().enumerate() {
             let mut rowstring = row.generate_row_fen_string();
             if index == 7 {
                 rowstring.push(' ');
@@ -935,17 +1208,34 @@ impl Board {
             PieceColor::White => "w ",
         });
 
-        //alright, now lets go get the castling rights.
+        //alright, now lets go get the castling rights. These are written the X-FEN way, so a standard game
+        //gets plain KQkq and a Chess960 rook only gets named by its file when K or Q would point at the wrong one.
         let mut castling_rights = String::new();
 
-        castling_rights.push_str(
-            &self
-                .white_side_information
-                .generate_fen_string()
-                .to_uppercase(),
-        );
-
-        castling_rights.push_str(&self.black_side_information.generate_fen_string());
+        for color in [PieceColor::White, PieceColor::Black] {
+            for is_kingside in [true, false] {
+                if !self.has_castling_rights(color, is_kingside) {
+                    continue;
+                }
+                let rook_letter = self
+                    .side_information(color)
+                    .castling_rook_letter(is_kingside);
+                let castling_char =
+                    if self.outermost_rook_letter(color, is_kingside) == Some(rook_letter) {
+                        if is_kingside {
+                            'k'
+                        } else {
+                            'q'
+                        }
+                    } else {
+                        (b'a' + rook_letter.eval() as u8) as char
+                    };
+                castling_rights.push(match color {
+                    PieceColor::White => castling_char.to_ascii_uppercase(),
+                    PieceColor::Black => castling_char,
+                });
+            }
+        }
 
         if (castling_rights.is_empty()) {
             castling_rights.push('-');
@@ -978,17 +1268,25 @@ impl Board {
         fen_output
     }
 
-    pub fn display_sides_information(&self) {
-        println!("White Side:");
-        println!("{}", self.white_side_information);
-        println!("Black Side");
-        println!("{}", self.black_side_information);
+    pub fn display_sides_information(&self) -> String {
+        format!(
+            "White Side:\n{}\nBlack Side\n{}",
+            self.white_side_information, self.black_side_information
+        )
     }
 
     pub fn get_turn(&self) -> PieceColor {
         self.turn
     }
 
+    pub fn get_half_turns(&self) -> u32 {
+        self.half_turns
+    }
+
+    pub fn get_full_turns(&self) -> u32 {
+        self.full_turns
+    }
+
     pub fn get_opt_passant_square(&self) -> Option<Coordinates> {
         self.opt_legal_passant_square
     }
@@ -1019,7 +1317,10 @@ impl Board {
         for coordinate in Self::board_coords() {
             match self.retreive_square(&coordinate) {
                 Ok(square) => {
-                    if !square.get_legal_targets(&coordinate, self).is_empty() {
+                    //only the side whose turn it is gets a say in whether there's a move to make.
+                    if self.square_belongs_to_turn(&square)
+                        && !square.get_legal_targets(&coordinate, self).is_empty()
+                    {
                         // if it's not empty we know that there are legal targets that would not put the king in danger, so we can break early. We only need one legal move to be available
                         legal_move_available = true;
                         break;
@@ -1034,6 +1335,48 @@ impl Board {
         legal_move_available
     }
 
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut legal_moves: Vec<Move> = Vec::new();
+        for coordinate in Self::board_coords() {
+            match self.retreive_square(&coordinate) {
+                Ok(square) => {
+                    if !self.square_belongs_to_turn(&square) {
+                        continue;
+                    }
+                    for target in square.get_legal_targets(&coordinate, self) {
+                        if promotion_required(&coordinate, &target, self) {
+                            //every promotion choice is its own move.
+                            for promotion_kind in PieceKind::promotion_choices() {
+                                legal_moves.push(Move::new(
+                                    coordinate,
+                                    target,
+                                    Some(*promotion_kind),
+                                ));
+                            }
+                        } else {
+                            legal_moves.push(Move::new(coordinate, target, None));
+                        }
+                    }
+                }
+                Err(_) => {
+                    panic!(
+                        "Error. Attempted to find legal moves for square {}, and it did not exist",
+                        coordinate
+                    );
+                }
+            }
+        }
+
+        legal_moves
+    }
+
+    fn square_belongs_to_turn(&self, square: &Square) -> bool {
+        match square {
+            Square::Full(piece) => piece.color == self.turn,
+            Square::Empty => false,
+        }
+    }
+
     pub fn show_me_legal_squares(&self, coords: &Coordinates) {
         match self.retreive_square(coords) {
             Ok(retrieved_square) => {
@@ -1043,169 +1386,215 @@ impl Board {
         }
     }
 
-    fn convert_row_usize(size: usize) -> Result<usize, &'static str> {
-        if size > 0 && size < 9 {
-            Ok(7 - (size - 1))
-        } else {
-            Err("attempted to perform subtraction out of bounds of usize")
+    pub fn retreive_square(&self, coords: &Coordinates) -> Result<Square, &'static str> {
+        match square_index(coords) {
+            Some(index) => Ok(self.square_at_index(index)),
+            None => Err("attempted to retreive a square outside of the board"),
         }
     }
 
-    pub fn retreive_square(&self, coords: &Coordinates) -> Result<Square, &'static str> {
-        match board_safe_isize_converter(coords.number) {
-            Ok(usize_number) => match board_safe_isize_converter(coords.letter.eval()) {
-                Ok(usize_letter) => match Self::convert_row_usize(usize_number) {
-                    Ok(converted_size) => {
-                        Ok((self.rows[converted_size].squares[usize_letter]).clone())
-                    }
-                    Err(error_text) => Err(error_text),
-                },
-                Err(letter_text) => Err(letter_text),
-            },
-            Err(number_text) => Err(number_text),
+    pub fn set_square(&mut self, coords: &Coordinates, square: Square) {
+        if let Some(index) = square_index(coords) {
+            self.place_square(index, square);
         }
     }
 
-    pub fn set_square(&mut self, coords: &Coordinates, square: Square) {
-        match board_safe_isize_converter(coords.number) {
-            Ok(usize_number) => match board_safe_isize_converter(coords.letter.eval()) {
-                Ok(usize_letter) => match Self::convert_row_usize(usize_number) {
-                    Ok(converted_rowsize) => {
-                        self.rows[converted_rowsize].squares[usize_letter] = square;
-                    }
-                    Err(_) => {}
-                },
-                Err(_) => (),
-            },
-            Err(_) => (),
+    //whether the side still has the right to castle that way, not whether it could castle right now.
+    pub fn has_castling_rights(&self, king_color: PieceColor, is_kingside_query: bool) -> bool {
+        match king_color {
+            PieceColor::Black => self
+                .black_side_information
+                .king_can_castle(is_kingside_query),
+            PieceColor::White => self
+                .white_side_information
+                .king_can_castle(is_kingside_query),
+        }
+    }
+
+    fn side_information(&self, color: PieceColor) -> &SideInformation {
+        match color {
+            PieceColor::Black => &self.black_side_information,
+            PieceColor::White => &self.white_side_information,
+        }
+    }
+
+    fn side_information_mut(&mut self, color: PieceColor) -> &mut SideInformation {
+        match color {
+            PieceColor::Black => &mut self.black_side_information,
+            PieceColor::White => &mut self.white_side_information,
+        }
+    }
+
+    //the rook furthest from the king on that side of it, along the home row. X-FEN's K and Q mean this rook.
+    pub fn outermost_rook_letter(
+        &self,
+        color: PieceColor,
+        is_kingside: bool,
+    ) -> Option<ColumnLetter> {
+        let home_row: isize = match color {
+            PieceColor::Black => 8,
+            PieceColor::White => 1,
+        };
+        let holds = |letter: &ColumnLetter, kind: PieceKind| {
+            self.retreive_square(&Coordinates::new(*letter, home_row))
+                == Ok(Square::Full(Piece { color, kind }))
+        };
+        let king_letter =
+            *ColumnLetter::iterator().find(|letter| holds(letter, PieceKind::King))?;
+
+        let is_rook = |letter: &&ColumnLetter| holds(letter, PieceKind::Rook);
+        if is_kingside {
+            ColumnLetter::iterator()
+                .rev()
+                .take_while(|letter| **letter != king_letter)
+                .find(is_rook)
+                .copied()
+        } else {
+            ColumnLetter::iterator()
+                .take_while(|letter| **letter != king_letter)
+                .find(is_rook)
+                .copied()
         }
     }
 
+    fn castling_squares(&self, king_color: PieceColor, is_kingside: bool) -> CastlingSquares {
+        //wherever they start, the king and rook finish on the same squares they would in standard chess.
+        let side_information = self.side_information(king_color);
+        let king_square = side_information.current_king_square;
+        let row_start = (king_square.number as usize - 1) * 8;
+        let (king_to_letter, rook_to_letter) = if is_kingside {
+            (ColumnLetter::G, ColumnLetter::F)
+        } else {
+            (ColumnLetter::C, ColumnLetter::D)
+        };
+        CastlingSquares {
+            king_from: row_start + king_square.letter.eval() as usize,
+            king_to: row_start + king_to_letter.eval() as usize,
+            rook_from: row_start
+                + side_information.castling_rook_letter(is_kingside).eval() as usize,
+            rook_to: row_start + rook_to_letter.eval() as usize,
+        }
+    }
+
+    //how a castling move is written. When the king and rook start where they do in standard chess, the king moves two
+    //squares like always. Otherwise the king takes its own rook, the way Chess960 GUIs write it, since a king that
+    //moves one square or not at all couldn't be told apart from an ordinary king move.
+    pub fn castling_move(&self, king_color: PieceColor, is_kingside: bool) -> Move {
+        let castling = self.castling_squares(king_color, is_kingside);
+        let side_information = self.side_information(king_color);
+        let standard_rook_letter = if is_kingside {
+            ColumnLetter::H
+        } else {
+            ColumnLetter::A
+        };
+        let is_standard_setup = side_information.current_king_square.letter == ColumnLetter::E
+            && side_information.castling_rook_letter(is_kingside) == standard_rook_letter;
+        Move::new(
+            coordinates_from_index(castling.king_from),
+            coordinates_from_index(if is_standard_setup {
+                castling.king_to
+            } else {
+                castling.rook_from
+            }),
+            None,
+        )
+    }
+
+    //which way a move castles, if it castles at all. Some(true) is kingside. This doesn't check the castle is legal.
+    pub fn castling_side(&self, chess_move: &Move) -> Option<bool> {
+        let king_color = match self.retreive_square(&chess_move.from) {
+            Ok(Square::Full(Piece {
+                color,
+                kind: PieceKind::King,
+            })) => color,
+            _ => return None,
+        };
+        [true, false].into_iter().find(|is_kingside| {
+            self.has_castling_rights(king_color, *is_kingside)
+                && self.castling_move(king_color, *is_kingside) == *chess_move
+        })
+    }
+
     pub fn king_can_castle(&self, king_color: PieceColor, is_kingside_query: bool) -> bool {
+        //do the cheap checks first, so we only go looking for danger when castling is actually on the table.
+        if !self.has_castling_rights(king_color, is_kingside_query)
+            || !self.castling_path_ready(king_color, is_kingside_query)
+        {
+            return false;
+        }
+
         if self.is_king_in_danger(king_color) {
             //now now, no castling out of check.
             return false;
         }
-        match king_color {
-            PieceColor::Black => {
-                self.black_side_information
-                    .king_can_castle(is_kingside_query)
-                    && self.is_castling_safe(king_color, is_kingside_query)
-            }
-            PieceColor::White => {
-                self.white_side_information
-                    .king_can_castle(is_kingside_query)
-                    && self.is_castling_safe(king_color, is_kingside_query)
-            }
-        }
-    }
 
-    fn is_castling_safe(&self, king_color: PieceColor, is_kingside_query: bool) -> bool {
-        //logical shorting means that we can safely blind check.
-        let mut copied_board = self.clone();
+        self.is_castling_safe(king_color, is_kingside_query)
+    }
 
-        let row_number: isize = match king_color {
-            PieceColor::Black => 8,
-            PieceColor::White => 1,
+    fn castling_path_ready(&self, king_color: PieceColor, is_kingside_query: bool) -> bool {
+        let castling = self.castling_squares(king_color, is_kingside_query);
+        let home_row_start = match king_color {
+            PieceColor::Black => 56,
+            PieceColor::White => 0,
+        };
+        let holds = |index: usize, kind: PieceKind| {
+            self.square_at_index(index)
+                == Square::Full(Piece {
+                    color: king_color,
+                    kind,
+                })
         };
+        if castling.king_from - castling.king_from % 8 != home_row_start
+            || !holds(castling.king_from, PieceKind::King)
+            || !holds(castling.rook_from, PieceKind::Rook)
+        {
+            return false;
+        }
 
-        let empty_square = Square::Empty;
-        match copied_board.retreive_square(&Coordinates {
-            letter: ColumnLetter::E,
-            number: row_number,
-        }) {
-            Ok(found_square) => {
-                let moved_square = found_square.clone();
-                if is_kingside_query {
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::E,
-                            number: row_number,
-                        },
-                        empty_square,
-                    );
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::F,
-                            number: row_number,
-                        },
-                        moved_square,
-                    );
-                    if copied_board.is_king_in_danger(king_color) {
-                        //keep your functions flat and return early.
-                        return false;
-                    }
-                    //ok, so we know that it's safe to keep going. Lets check the next state.
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::F,
-                            number: row_number,
-                        },
-                        empty_square,
-                    );
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::G,
-                            number: row_number,
-                        },
-                        moved_square,
-                    );
-                    if copied_board.is_king_in_danger(king_color) {
-                        return false;
-                    }
-                    // we don't need to check hopping the rook over the king, because there isn't a way to threaten the king by moving that rook.
-                } else {
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::E,
-                            number: row_number,
-                        },
-                        empty_square,
-                    );
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::D,
-                            number: row_number,
-                        },
-                        moved_square,
-                    );
-                    if copied_board.is_king_in_danger(king_color) {
-                        //keep your functions flat and return early.
-                        return false;
-                    }
-                    //ok, so we know that it's safe to keep going. Lets check the next state.
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::D,
-                            number: row_number,
-                        },
-                        empty_square,
-                    );
-                    copied_board.set_square(
-                        &Coordinates {
-                            letter: ColumnLetter::C,
-                            number: row_number,
-                        },
-                        moved_square,
-                    );
-                    if copied_board.is_king_in_danger(king_color) {
-                        return false;
-                    }
-                }
+        //everything the king and rook cross or land on has to be empty, apart from the two of them.
+        let corners = [
+            castling.king_from,
+            castling.king_to,
+            castling.rook_from,
+            castling.rook_to,
+        ];
+        let lowest = corners.iter().min().copied().unwrap_or(castling.king_from);
+        let highest = corners.iter().max().copied().unwrap_or(castling.king_from);
+        (lowest..=highest).all(|index| {
+            index == castling.king_from
+                || index == castling.rook_from
+                || self.square_at_index(index) == Square::Empty
+        })
+    }
 
-                //if we made it here, then it's safe to return true.
-                true
-            }
-            Err(_) => {
-                panic!(
-                    "You failed to retreive the square the king should be on at E{}",
-                    row_number
-                )
-            }
+    fn is_castling_safe(&self, king_color: PieceColor, is_kingside_query: bool) -> bool {
+        let castling = self.castling_squares(king_color, is_kingside_query);
+        let enemy_color = king_color.get_inverse_color();
+
+        //every square the king crosses or lands on has to be out of reach. Where it starts was checked already.
+        let lowest = castling.king_from.min(castling.king_to);
+        let highest = castling.king_from.max(castling.king_to);
+        if (lowest..=highest)
+            .any(|index| index != castling.king_from && self.square_attacked_by(index, enemy_color))
+        {
+            //keep your functions flat and return early.
+            return false;
         }
+
+        //in Chess960 the rook can be all that's shielding the king's landing square, so look again without it.
+        let mut copied_board = self.clone();
+        copied_board.place_square(castling.rook_from, Square::Empty);
+        !copied_board.square_attacked_by(castling.king_to, enemy_color)
     }
 
     pub fn remove_castling_rights(&mut self, side_color: PieceColor, is_kingside: bool) {
+        self.hash ^= self.state_hash();
+        self.remove_castling_rights_unhashed(side_color, is_kingside);
+        self.hash ^= self.state_hash();
+    }
+
+    fn remove_castling_rights_unhashed(&mut self, side_color: PieceColor, is_kingside: bool) {
+        //make_move settles the hash once the whole move is done, so it comes through here.
         match side_color {
             PieceColor::Black => self
                 .black_side_information
@@ -1216,97 +1605,158 @@ impl Board {
         }
     }
 
-    pub fn is_king_in_danger(&self, king_color: PieceColor) -> bool {
-        let target_king_coordinates = match king_color {
-            PieceColor::Black => self.black_side_information.current_king_square,
-            PieceColor::White => self.white_side_information.current_king_square,
+    fn remove_castling_rights_from_corner(&mut self, coords: &Coordinates, rook_color: PieceColor) {
+        let home_row: isize = match rook_color {
+            PieceColor::Black => 8,
+            PieceColor::White => 1,
         };
-
-        for letter in ColumnLetter::iterator() {
-            for number in 1..9 {
-                let from_coords = Coordinates {
-                    letter: *letter,
-                    number: number,
-                };
-                match self.retreive_square(&from_coords) {
-                    Ok(gotten_square) => {
-                        match gotten_square {
-                            Square::Full(piece) => {
-                                // we only care about the ability of other pieces to tkae our king;
-                                if piece.color != king_color {
-                                    //only pieces of the opposite color can threaten the king
-                                    if self.square_threatens_square(
-                                        &from_coords,
-                                        &target_king_coordinates,
-                                    ) {
-                                        // oops, we found a square that threatens the king. Can't allow that!
-                                        return true;
-                                    }
-                                }
-                            }
-                            Square::Empty => (),
-                        }
-                    }
-                    Err(text) => {
-                        panic!("oops we tried to retrieve an illegal square in is_king_in_danger with error {}", text)
-                    }
+        if coords.number == home_row {
+            //we can just do this dumbly, since it doesn't cost much, and firing it off every time ensures safety.
+            //Lesson: sometimes it's cheaper to just ensure bool state than it is to check every time.
+            for is_kingside in [true, false] {
+                if coords.letter
+                    == self
+                        .side_information(rook_color)
+                        .castling_rook_letter(is_kingside)
+                {
+                    self.remove_castling_rights_unhashed(rook_color, is_kingside);
                 }
             }
         }
+    }
+
+    pub fn is_king_in_danger(&self, king_color: PieceColor) -> bool {
+        let king_bitboard = self.piece_bitboard(Piece {
+            color: king_color,
+            kind: PieceKind::King,
+        });
+
+        if king_bitboard == EMPTY_BITBOARD {
+            //no king, no danger. Making sure there is one is the fen parser's job.
+            return false;
+        }
 
-        false
+        self.square_attacked_by(
+            king_bitboard.trailing_zeros() as usize,
+            king_color.get_inverse_color(),
+        )
     }
 
-    pub fn square_threatens_square(&self, from: &Coordinates, to: &Coordinates) -> bool {
-        match self.retreive_square(from) {
-            Ok(found_square) => {
-                match found_square {
-                    Square::Full(piece) => {
-                        let distance_information = measure_distance(from, to);
-                        let (legal, _, _, _, _, _, _, _) = parse_move_legality(from, to, self);
-                        if legal {
-                            match piece.kind {
-                                PieceKind::Pawn => {
-                                    //pawns are a special case because they can move up or down, but can only take on the diagonal.
-                                    distance_information.distance == 2
-                                        && (distance_information.move_direction
-                                            != MoveDirection::Up
-                                            && distance_information.move_direction
-                                                != MoveDirection::Down)
-                                }
-                                _ => true,
-                            }
-                        } else {
-                            false
-                        }
-                    }
-                    Square::Empty => false, //can't threaten another square with an empty square.
-                }
-            }
-            Err(_) => {
-                panic!("oops, we tried to check if a square threatens another but that square was illegal!")
+    fn square_attacked_by(&self, index: usize, attacker_color: PieceColor) -> bool {
+        self.attackers_of_index(index, attacker_color) != EMPTY_BITBOARD
+    }
+
+    fn attackers_of_index(&self, index: usize, attacker_color: PieceColor) -> Bitboard {
+        let occupied = self.occupied_bitboard();
+        let attacker_bitboard = |kind: PieceKind| {
+            self.piece_bitboard(Piece {
+                color: attacker_color,
+                kind,
+            })
+        };
+        let diagonal_sliders =
+            attacker_bitboard(PieceKind::Bishop) | attacker_bitboard(PieceKind::Queen);
+        let straight_sliders =
+            attacker_bitboard(PieceKind::Rook) | attacker_bitboard(PieceKind::Queen);
+
+        //we look outwards from the target square. A pawn of the other color standing here would attack exactly the squares an attacking pawn could take from.
+        (pawn_attacks(index, attacker_color.get_inverse_color())
+            & attacker_bitboard(PieceKind::Pawn))
+            | (knight_attacks(index) & attacker_bitboard(PieceKind::Knight))
+            | (king_attacks(index) & attacker_bitboard(PieceKind::King))
+            | (bishop_attacks(index, occupied) & diagonal_sliders)
+            | (rook_attacks(index, occupied) & straight_sliders)
+    }
+
+    //every piece of attacker_color that attacks the square, whether or not it could legally move there.
+    pub fn attackers_of(&self, square: &Coordinates, attacker_color: PieceColor) -> Bitboard {
+        match square_index(square) {
+            Some(index) => self.attackers_of_index(index, attacker_color),
+            None => EMPTY_BITBOARD,
+        }
+    }
+
+    pub fn is_square_attacked(&self, square: &Coordinates, attacker_color: PieceColor) -> bool {
+        self.attackers_of(square, attacker_color) != EMPTY_BITBOARD
+    }
+
+    //the pieces giving check to the side to move. Two of them means only a king move gets out of it.
+    pub fn checkers(&self) -> Bitboard {
+        let king_bitboard = self.piece_bitboard(Piece {
+            color: self.turn,
+            kind: PieceKind::King,
+        });
+        match square_indices(king_bitboard).next() {
+            Some(king_index) => self.attackers_of_index(king_index, self.turn.get_inverse_color()),
+            None => EMPTY_BITBOARD,
+        }
+    }
+
+    //pieces of this color that can't leave the line between their king and an enemy slider without exposing the king.
+    pub fn pinned_pieces(&self, color: PieceColor) -> Vec<Pin> {
+        let mut pins: Vec<Pin> = Vec::new();
+        let king_index = match square_indices(self.piece_bitboard(Piece {
+            color,
+            kind: PieceKind::King,
+        }))
+        .next()
+        {
+            Some(king_index) => king_index,
+            None => return pins,
+        };
+
+        let enemy_color = color.get_inverse_color();
+        let enemy_bitboard = |kind: PieceKind| {
+            self.piece_bitboard(Piece {
+                color: enemy_color,
+                kind,
+            })
+        };
+        //sliders that would see the king on an empty board are the only ones that could pin anything.
+        let possible_pinners = (rook_attacks(king_index, EMPTY_BITBOARD)
+            & (enemy_bitboard(PieceKind::Rook) | enemy_bitboard(PieceKind::Queen)))
+            | (bishop_attacks(king_index, EMPTY_BITBOARD)
+                & (enemy_bitboard(PieceKind::Bishop) | enemy_bitboard(PieceKind::Queen)));
+
+        for pinner_index in square_indices(possible_pinners) {
+            let between = squares_between(king_index, pinner_index);
+            let blockers = between & self.occupied_bitboard();
+            if blockers.count_ones() == 1 && blockers & self.color_bitboard(color) != EMPTY_BITBOARD
+            {
+                pins.push(Pin {
+                    pinned: coordinates_from_index(blockers.trailing_zeros() as usize),
+                    pinner: coordinates_from_index(pinner_index),
+                    ray: between | square_bit(pinner_index),
+                });
             }
         }
+
+        pins
     }
 
-    fn panic_in_twixt_hither() {
-        panic!("attempting to access square illegally in ")
+    fn attacks_from_index(&self, index: usize) -> Bitboard {
+        let occupied = self.occupied_bitboard();
+        match self.square_at_index(index) {
+            Square::Full(piece) => match piece.kind {
+                PieceKind::Pawn => pawn_attacks(index, piece.color),
+                PieceKind::Knight => knight_attacks(index),
+                PieceKind::Bishop => bishop_attacks(index, occupied),
+                PieceKind::Rook => rook_attacks(index, occupied),
+                PieceKind::Queen => queen_attacks(index, occupied),
+                PieceKind::King => king_attacks(index),
+            },
+            Square::Empty => EMPTY_BITBOARD, //can't threaten another square with an empty square.
+        }
     }
 
-    fn inner_path_clear_checking(&self, path_clear: &mut bool, coords: &Coordinates) -> bool {
-        match self.retreive_square(coords) {
-            Ok(found_square) => {
-                match found_square {
-                    Square::Full(_) => {
-                        *path_clear = false;
-                        false
-                    }
-                    Square::Empty => true, //Expected and fine
-                }
+    pub fn square_threatens_square(&self, from: &Coordinates, to: &Coordinates) -> bool {
+        //a threat doesn't care whether the attacker is pinned or whose turn it is, only how the piece moves.
+        match square_index(from) {
+            Some(from_index) => {
+                self.attacks_from_index(from_index) & coordinates_bit(to) != EMPTY_BITBOARD
             }
-            Err(_) => {
-                Self::panic_in_twixt_hither();
-                false
+            None => {
+                panic!("oops, we tried to check if a square threatens another but that square was illegal!")
             }
         }
     }
@@ -1315,173 +1765,20 @@ impl Board {
         &self,
         from: &Coordinates,
         to: &Coordinates,
-        direction: MoveDirection,
+        _direction: MoveDirection,
     ) -> bool {
-        let mut path_clear = true;
-
-        let from_letter_value = from.letter.eval();
-        let to_letter_value = to.letter.eval();
-        let from_number_value = from.number;
-        let to_number_value = to.number;
-        match direction {
-            MoveDirection::Down => {
-                for i in (to_number_value + 1)..(from_number_value) {
-                    if !self.inner_path_clear_checking(
-                        &mut path_clear,
-                        &Coordinates {
-                            letter: from.letter,
-                            number: i,
-                        },
-                    ) {
-                        break;
-                    }
-                }
-            }
-            MoveDirection::Up => {
-                for i in (from_number_value + 1)..to_number_value {
-                    if !self.inner_path_clear_checking(
-                        &mut path_clear,
-                        &Coordinates {
-                            letter: from.letter,
-                            number: i,
-                        },
-                    ) {
-                        break;
-                    }
-                }
+        match (square_index(from), square_index(to)) {
+            (Some(from_index), Some(to_index)) => {
+                //knight hooks and illegal moves have nothing between them, so they always come back clear.
+                squares_between(from_index, to_index) & self.occupied_bitboard() == EMPTY_BITBOARD
             }
-            MoveDirection::Left => {
-                for i in (to_letter_value + 1)..from_letter_value {
-                    match ColumnLetter::construct_letter_from_isize(i) {
-                        Ok(found_letter) => {
-                            if !self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to access out of bounds");
-                        }
-                    }
-                }
-            }
-            MoveDirection::Right => {
-                for i in (from_letter_value + 1)..to_letter_value {
-                    match ColumnLetter::construct_letter_from_isize(i) {
-                        Ok(found_letter) => {
-                            if !self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to acces out of bounds");
-                        }
-                    }
-                }
-            }
-            MoveDirection::Diagonal(DiagonalDirection::DownLeft) => {
-                let distance = from_letter_value - to_letter_value;
-                for i in 1..distance {
-                    match ColumnLetter::construct_letter_from_isize(from_letter_value - i) {
-                        Ok(found_letter) => {
-                            if self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value - i,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to access out of bounds")
-                        }
-                    }
-                }
-            }
-            MoveDirection::Diagonal(DiagonalDirection::DownRight) => {
-                let distance = to_letter_value - from_letter_value;
-                for i in 1..distance {
-                    match ColumnLetter::construct_letter_from_isize(from_letter_value + i) {
-                        Ok(found_letter) => {
-                            if !self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value - i,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to access out of bounds")
-                        }
-                    }
-                }
-            }
-            MoveDirection::Diagonal(DiagonalDirection::UpLeft) => {
-                let distance = to_number_value - from_number_value;
-                for i in 1..distance {
-                    match ColumnLetter::construct_letter_from_isize(from_letter_value - i) {
-                        Ok(found_letter) => {
-                            if !self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value + i,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to access out of bounds")
-                        }
-                    }
-                }
-            }
-            MoveDirection::Diagonal(DiagonalDirection::UpRight) => {
-                let distance = to_letter_value - from_letter_value;
-                for i in 1..distance {
-                    // we can rely on the distance being equal, otherwise the move is illegal.
-                    match ColumnLetter::construct_letter_from_isize(from_letter_value + i) {
-                        Ok(found_letter) => {
-                            if !self.inner_path_clear_checking(
-                                &mut path_clear,
-                                &Coordinates {
-                                    letter: found_letter,
-                                    number: from_number_value + i,
-                                },
-                            ) {
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            panic!("attempted to access out of bounds")
-                        }
-                    }
-                }
+            _ => {
+                panic!("attempted to access out of bounds")
             }
-            _ => (), //if we are here, the move is either a JHook, which is allowed to jump, or illegal,
         }
-
-        path_clear
     }
 
-    fn update_king_location(&mut self, coords: Coordinates, king_color: PieceColor) {
+    pub(crate) fn update_king_location(&mut self, coords: Coordinates, king_color: PieceColor) {
         match king_color {
             PieceColor::Black => self
                 .black_side_information
@@ -1492,185 +1789,31 @@ impl Board {
         }
     }
 
-    fn castle(&mut self, king_color: PieceColor, is_kingside: bool) {
-        let row_number = match king_color {
-            PieceColor::White => 1,
-            PieceColor::Black => 8,
-        };
-        let opt_pulled_king = self
-            .retreive_square(&Coordinates {
-                letter: ColumnLetter::E,
-                number: row_number,
-            })
-            .clone();
-        let opt_pulled_rook = match is_kingside {
-            true => self
-                .retreive_square(&Coordinates {
-                    letter: ColumnLetter::H,
-                    number: row_number,
-                })
-                .clone(),
-            false => self
-                .retreive_square(&Coordinates {
-                    letter: ColumnLetter::A,
-                    number: row_number,
-                })
-                .clone(),
-        };
-        let empty_square = Square::Empty;
-
-        match opt_pulled_king {
-            Ok(pulled_king) => match opt_pulled_rook {
-                Ok(pulled_rook) => {
-                    match is_kingside {
-                        true => {
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::H,
-                                    number: row_number,
-                                },
-                                empty_square,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::E,
-                                    number: row_number,
-                                },
-                                empty_square,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::G,
-                                    number: row_number,
-                                },
-                                pulled_king,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::F,
-                                    number: row_number,
-                                },
-                                pulled_rook,
-                            );
-                        }
-                        false => {
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::A,
-                                    number: row_number,
-                                },
-                                empty_square,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::E,
-                                    number: row_number,
-                                },
-                                empty_square,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::C,
-                                    number: row_number,
-                                },
-                                pulled_king,
-                            );
-                            self.set_square(
-                                &Coordinates {
-                                    letter: ColumnLetter::D,
-                                    number: row_number,
-                                },
-                                pulled_rook,
-                            );
-                        }
-                    };
-                }
-                Err(_) => {
-                    panic!("attempted to castle, but could not retreive rook")
-                }
-            },
-            Err(_) => {
-                panic!("attempted to castle, but could not retreive king")
-            }
-        }
-    }
-
-    pub fn move_piece(&mut self, from: &Coordinates, to: &Coordinates) -> MoveResult {
+    pub fn move_piece(&mut self, requested_move: &Move) -> MoveResult {
         let mut move_result: MoveResult;
-        let opt_from_square = self.retreive_square(&from);
+        let from = &requested_move.from;
+        let to = &requested_move.to;
+        let opt_from_square = self.retreive_square(from);
         match opt_from_square {
             Ok(from_square) => {
-                let replacement_square = from_square.clone();
                 match from_square {
                     Square::Full(piece) => {
                         if piece.color == self.turn {
-                            let (
-                                move_legal,
-                                taking_piece,
-                                target_piece_color,
-                                target_piece_kind,
-                                move_direction,
-                                move_distance,
-                                opt_passant_target,
-                                opt_new_passant_legal,
-                            ) = parse_move_legality(from, to, self);
+                            let (move_legal, ..) =
+                                parse_move_legality(from, to, requested_move.opt_promotion, self);
 
                             if move_legal {
-                                // if we're moving the king we need to update his coords
-                                match piece.kind {
-                                    PieceKind::King => {
-                                        // if we got here, the rule checker already knows that this move is safe and legal. Lets check if we're castling, then update appropriately
-                                        if move_distance == 2 {
-                                            if move_direction == MoveDirection::Right {
-                                                self.castle(piece.color, true);
-                                            } else if move_direction == MoveDirection::Left {
-                                                self.castle(piece.color, false);
-                                            }
-                                        }
-                                        self.update_king_location(*from, piece.color);
-                                    }
-                                    PieceKind::Rook => {
-                                        // we need to check if they're moving off their original square, and negate castling rights as necessary.
-                                        if from.letter == ColumnLetter::A {
-                                            //we can just do this dumbly, since it doesn't cost much, and firing it off every time ensures safety.
-                                            //Lesson: sometimes it's cheaper to just ensure bool state than it is to check every time.
-                                            self.remove_castling_rights(piece.color, false);
-                                        } else if from.letter == ColumnLetter::H {
-                                            self.remove_castling_rights(piece.color, true);
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                                self.opt_legal_passant_square = opt_new_passant_legal;
-                                self.set_square(&from, Square::Empty);
-                                self.set_square(&to, replacement_square);
-                                match opt_passant_target {
-                                    Some(passant_target) => {
-                                        self.set_square(&passant_target, Square::Empty);
-                                    }
-                                    None => {}
-                                }
-                                if taking_piece {
-                                    self.add_piece_to_kills(target_piece_kind, target_piece_color);
-                                }
-                                if taking_piece || piece.kind == PieceKind::Pawn {
-                                    self.half_turns = 0;
-                                } else {
-                                    self.half_turns += 1;
-                                }
-                                match self.turn {
-                                    PieceColor::Black => {
-                                        self.turn = PieceColor::White;
-                                        self.full_turns += 1;
-                                    }
-                                    PieceColor::White => self.turn = PieceColor::Black,
-                                }
-                                let opponent_color = match piece.color {
-                                    PieceColor::Black => PieceColor::White,
-                                    PieceColor::White => PieceColor::Black,
-                                };
+                                // if we got here, the rule checker already knows that this move is safe and legal.
+                                self.make_move(requested_move);
+                                let opponent_color = piece.color.get_inverse_color();
                                 //we can make the move they are requesting. Lets check what state this leaves the board in.
-                                match king_checkmate_state(opponent_color, &self) {
+                                match king_checkmate_state(opponent_color, self) {
+                                    //neither side can mate any more, whatever happens next. The check doesn't matter.
+                                    MateState::Check | MateState::Safe
+                                        if insufficient_mating_material(self) =>
+                                    {
+                                        move_result = MoveResult::InsufficientMaterial;
+                                    }
                                     MateState::Check => match opponent_color {
                                         PieceColor::Black => {
                                             move_result = MoveResult::BlackKingChecked;
@@ -1711,106 +1854,284 @@ impl Board {
             }
         }
 
-        if self.half_turns == 100 {
-            move_result = MoveResult::Stalemate;
+        //seventy five moves without a capture or a pawn move ends the game on its own. A mate on that very move still stands.
+        if self.half_turns >= 150
+            && matches!(
+                move_result,
+                MoveResult::CompletedSafely
+                    | MoveResult::BlackKingChecked
+                    | MoveResult::WhiteKingChecked
+            )
+        {
+            move_result = MoveResult::SeventyFiveMoveRule;
         }
 
         move_result
     }
 
-    pub fn add_piece_to_kills(&mut self, piece_kind: PieceKind, piece_color: PieceColor) {
-        match piece_color {
-            PieceColor::Black => self.white_side_information.add_taken_piece(piece_kind),
-            PieceColor::White => self.black_side_information.add_taken_piece(piece_kind),
+    pub fn make_move(&mut self, requested_move: &Move) -> Undo {
+        //no legality checking here. This is for callers that already know the move is legal, or that are about to find out and step back.
+        let from = requested_move.from;
+        let to = requested_move.to;
+        let (from_index, to_index) = match (square_index(&from), square_index(&to)) {
+            (Some(from_index), Some(to_index)) => (from_index, to_index),
+            _ => {
+                panic!("attempted to make a move to or from a square outside of the board")
+            }
+        };
+        let moved_piece = match self.square_at_index(from_index) {
+            Square::Full(piece) => piece,
+            Square::Empty => {
+                panic!("attempted to make a move from an empty square at {}", from)
+            }
+        };
+
+        let opt_castling = if moved_piece.kind == PieceKind::King {
+            self.castling_side(requested_move)
+                .map(|is_kingside| self.castling_squares(moved_piece.color, is_kingside))
+        } else {
+            None
+        };
+
+        let opt_captured = match self.square_at_index(to_index) {
+            //in Chess960 the king can castle by stepping onto its own rook, which doesn't take anything.
+            _ if opt_castling.is_some() => None,
+            Square::Full(captured_piece) => Some((to_index, captured_piece)),
+            Square::Empty => {
+                if moved_piece.kind == PieceKind::Pawn && from.letter != to.letter {
+                    //a pawn moving sideways onto an empty square is taking en passant, and the pawn it takes sits beside it.
+                    let passant_index = from_index - from_index % 8 + to_index % 8;
+                    match self.square_at_index(passant_index) {
+                        Square::Full(captured_piece) => Some((passant_index, captured_piece)),
+                        Square::Empty => None,
+                    }
+                } else {
+                    None
+                }
+            }
+        };
+
+        let undo = Undo {
+            made_move: *requested_move,
+            moved_piece,
+            opt_captured,
+            opt_castling,
+            previous_passant_square: self.opt_legal_passant_square,
+            previous_white_side: self.white_side_information,
+            previous_black_side: self.black_side_information,
+            previous_half_turns: self.half_turns,
+            previous_full_turns: self.full_turns,
+            previous_hash: self.hash,
+        };
+
+        //take the old turn, castling and en passant out of the hash now, and put the new ones in once the move is done.
+        self.hash ^= self.state_hash();
+
+        match moved_piece.kind {
+            PieceKind::King => {
+                let king_to = match opt_castling {
+                    Some(castling) => coordinates_from_index(castling.king_to),
+                    None => to,
+                };
+                self.update_king_location(king_to, moved_piece.color);
+            }
+            PieceKind::Rook => {
+                // we need to check if they're moving off their original square, and negate castling rights as necessary.
+                self.remove_castling_rights_from_corner(&from, moved_piece.color);
+            }
+            _ => {}
+        }
+
+        if let Some((captured_index, captured_piece)) = opt_captured {
+            if captured_piece.kind == PieceKind::Rook {
+                //a rook that gets taken on its starting square can't be castled with either.
+                self.remove_castling_rights_from_corner(
+                    &coordinates_from_index(captured_index),
+                    captured_piece.color,
+                );
+            }
+            self.place_square(captured_index, Square::Empty);
+        }
+
+        self.opt_legal_passant_square = if moved_piece.kind == PieceKind::Pawn
+            && isize_difference(from.number, to.number) == 2
+        {
+            Some(Coordinates {
+                letter: from.letter,
+                number: (from.number + to.number) / 2,
+            })
+        } else {
+            None
+        };
+
+        match (opt_castling, requested_move.opt_promotion) {
+            (Some(castling), _) => {
+                //lift both pieces before putting either down, since either one can land where the other started.
+                self.place_square(castling.king_from, Square::Empty);
+                self.place_square(castling.rook_from, Square::Empty);
+                self.place_square(castling.king_to, Square::Full(moved_piece));
+                self.place_square(
+                    castling.rook_to,
+                    Square::Full(Piece {
+                        color: moved_piece.color,
+                        kind: PieceKind::Rook,
+                    }),
+                );
+            }
+            (None, Some(promotion_kind)) => {
+                self.place_square(from_index, Square::Empty);
+                self.place_square(
+                    to_index,
+                    Square::Full(Piece {
+                        color: moved_piece.color,
+                        kind: promotion_kind,
+                    }),
+                );
+            }
+            (None, None) => {
+                self.place_square(from_index, Square::Empty);
+                self.place_square(to_index, Square::Full(moved_piece));
+            }
+        }
+
+        if requested_move.opt_promotion.is_some() {
+            //promoted pieces muddy the waters, so we count from the board instead of keeping a tally.
+            self.adjust_taken_pieces();
+        } else if let Some((_, captured_piece)) = opt_captured {
+            self.add_piece_to_kills(captured_piece.kind, captured_piece.color);
         }
+
+        if opt_captured.is_some() || moved_piece.kind == PieceKind::Pawn {
+            self.half_turns = 0;
+        } else {
+            self.half_turns += 1;
+        }
+
+        if self.turn == PieceColor::Black {
+            self.full_turns += 1;
+        }
+        self.turn = self.turn.get_inverse_color();
+        self.hash ^= self.state_hash();
+
+        undo
     }
 
-    pub fn adjust_taken_pieces(&mut self) {
-        let mut black_pawn_count = 0;
-        let mut black_rook_count = 0;
-        let mut black_knight_count = 0;
-        let mut black_bishop_count = 0;
-        let mut black_queen_count = 0;
-        let mut black_king_count = 0;
-
-        let mut white_pawn_count = 0;
-        let mut white_rook_count = 0;
-        let mut white_knight_count = 0;
-        let mut white_bishop_count = 0;
-        let mut white_queen_count = 0;
-        let mut white_king_count = 0;
-
-        for row in self.rows.iter() {
-            for square in row.squares.iter() {
-                match square {
-                    Square::Full(piece) => match piece.kind {
-                        PieceKind::Pawn => {
-                            if piece.color == PieceColor::Black {
-                                black_pawn_count += 1;
-                            } else {
-                                white_pawn_count += 1;
-                            }
-                        }
-                        PieceKind::Rook => {
-                            if piece.color == PieceColor::Black {
-                                black_rook_count += 1;
-                            } else {
-                                white_rook_count += 1;
-                            }
-                        }
-                        PieceKind::Knight => {
-                            if piece.color == PieceColor::Black {
-                                black_knight_count += 1;
-                            } else {
-                                white_knight_count += 1;
-                            }
-                        }
-                        PieceKind::Bishop => {
-                            if piece.color == PieceColor::Black {
-                                black_bishop_count += 1;
-                            } else {
-                                white_bishop_count += 1;
-                            }
-                        }
-                        PieceKind::Queen => {
-                            if piece.color == PieceColor::Black {
-                                black_queen_count += 1;
-                            } else {
-                                white_queen_count += 1;
-                            }
-                        }
-                        PieceKind::King => {
-                            if piece.color == PieceColor::Black {
-                                black_king_count += 1;
-                            } else {
-                                white_king_count += 1;
-                            }
-                        }
-                    },
-                    Square::Empty => {}
+    pub fn unmake_move(&mut self, undo: Undo) {
+        let from_index = square_index(&undo.made_move.from)
+            .expect("an undo should only ever hold a move that was made on the board");
+        let to_index = square_index(&undo.made_move.to)
+            .expect("an undo should only ever hold a move that was made on the board");
+
+        //take things back in the opposite order to how make_move put them down.
+        match undo.opt_castling {
+            Some(castling) => {
+                self.place_square(castling.rook_to, Square::Empty);
+                self.place_square(castling.king_to, Square::Empty);
+                self.place_square(
+                    castling.rook_from,
+                    Square::Full(Piece {
+                        color: undo.moved_piece.color,
+                        kind: PieceKind::Rook,
+                    }),
+                );
+                self.place_square(castling.king_from, Square::Full(undo.moved_piece));
+            }
+            None => {
+                self.place_square(to_index, Square::Empty);
+                self.place_square(from_index, Square::Full(undo.moved_piece));
+                if let Some((captured_index, captured_piece)) = undo.opt_captured {
+                    self.place_square(captured_index, Square::Full(captured_piece));
                 }
             }
         }
 
+        self.turn = undo.moved_piece.color;
+        self.opt_legal_passant_square = undo.previous_passant_square;
+        self.white_side_information = undo.previous_white_side;
+        self.black_side_information = undo.previous_black_side;
+        self.half_turns = undo.previous_half_turns;
+        self.full_turns = undo.previous_full_turns;
+        self.hash = undo.previous_hash;
+    }
+
+    pub fn add_piece_to_kills(&mut self, piece_kind: PieceKind, piece_color: PieceColor) {
+        //if a side still has at least as many of these as it started with, the one we took must have been a promoted pawn.
+        let remaining = self.count_pieces(Piece {
+            color: piece_color,
+            kind: piece_kind,
+        });
+        let recorded_kind =
+            if piece_kind != PieceKind::Pawn && remaining >= piece_kind.starting_count() {
+                PieceKind::Pawn
+            } else {
+                piece_kind
+            };
+        match piece_color {
+            PieceColor::Black => self.white_side_information.add_taken_piece(recorded_kind),
+            PieceColor::White => self.black_side_information.add_taken_piece(recorded_kind),
+        }
+    }
+
+    pub fn count_pieces(&self, target_piece: Piece) -> i32 {
+        self.piece_bitboard(target_piece).count_ones() as i32
+    }
+
+    pub fn adjust_taken_pieces(&mut self) {
+        let count_black = |kind: PieceKind| {
+            self.count_pieces(Piece {
+                color: PieceColor::Black,
+                kind,
+            })
+        };
+        let mut black_pawn_count = count_black(PieceKind::Pawn);
+        let mut black_rook_count = count_black(PieceKind::Rook);
+        let mut black_knight_count = count_black(PieceKind::Knight);
+        let mut black_bishop_count = count_black(PieceKind::Bishop);
+        let mut black_queen_count = count_black(PieceKind::Queen);
+        let black_king_count = count_black(PieceKind::King);
+
+        let count_white = |kind: PieceKind| {
+            self.count_pieces(Piece {
+                color: PieceColor::White,
+                kind,
+            })
+        };
+        let mut white_pawn_count = count_white(PieceKind::Pawn);
+        let mut white_rook_count = count_white(PieceKind::Rook);
+        let mut white_knight_count = count_white(PieceKind::Knight);
+        let mut white_bishop_count = count_white(PieceKind::Bishop);
+        let mut white_queen_count = count_white(PieceKind::Queen);
+        let white_king_count = count_white(PieceKind::King);
+
         if white_king_count < 1 || black_king_count < 1 {
             panic!("Oops! It looks like you don't have a king on the board! How do you expect to win the game???");
         }
 
+        //anything beyond the starting count of a piece had to come from a promoted pawn, so those pawns weren't taken.
+        let black_promoted_count = (black_rook_count - 2).max(0)
+            + (black_knight_count - 2).max(0)
+            + (black_bishop_count - 2).max(0)
+            + (black_queen_count - 1).max(0);
+        let white_promoted_count = (white_rook_count - 2).max(0)
+            + (white_knight_count - 2).max(0)
+            + (white_bishop_count - 2).max(0)
+            + (white_queen_count - 1).max(0);
+
         //now lets adjust to taken counts by reduction
-        black_pawn_count = 8 - black_pawn_count;
-        white_pawn_count = 8 - white_pawn_count;
+        black_pawn_count = (8 - (black_pawn_count + black_promoted_count)).max(0);
+        white_pawn_count = (8 - (white_pawn_count + white_promoted_count)).max(0);
 
-        black_rook_count = 2 - black_rook_count;
-        white_rook_count = 2 - white_rook_count;
+        black_rook_count = (2 - black_rook_count).max(0);
+        white_rook_count = (2 - white_rook_count).max(0);
 
-        black_knight_count = 2 - black_knight_count;
-        white_knight_count = 2 - white_knight_count;
+        black_knight_count = (2 - black_knight_count).max(0);
+        white_knight_count = (2 - white_knight_count).max(0);
 
-        black_bishop_count = 2 - black_bishop_count;
-        white_bishop_count = 2 - white_bishop_count;
+        black_bishop_count = (2 - black_bishop_count).max(0);
+        white_bishop_count = (2 - white_bishop_count).max(0);
 
-        black_queen_count = 1 - black_queen_count;
-        white_queen_count = 1 - white_queen_count;
+        black_queen_count = (1 - black_queen_count).max(0);
+        white_queen_count = (1 - white_queen_count).max(0);
 
         self.white_side_information.adjust_taken_pieces(
             black_pawn_count,
@@ -1828,51 +2149,54 @@ impl Board {
         );
     }
 
-    pub fn show_taken_pieces(&self, color: PieceColor) {
-        print!("\n< ");
-        match color {
-            PieceColor::Black => {
-                for piece in self.black_side_information.taken_pieces.iter() {
-                    print!("{} ", piece);
-                }
-            }
-            PieceColor::White => {
-                for piece in self.white_side_information.taken_pieces.iter() {
-                    print!("{} ", piece);
+    pub fn locate_kings(&mut self) {
+        for color in PieceColor::iterator() {
+            let king_bitboard = self.piece_bitboard(Piece {
+                color: *color,
+                kind: PieceKind::King,
+            });
+            if let Some(king_index) = square_indices(king_bitboard).next() {
+                let king_square = coordinates_from_index(king_index);
+                match color {
+                    PieceColor::Black => self.black_side_information.set_king_square(king_square),
+                    PieceColor::White => self.white_side_information.set_king_square(king_square),
                 }
             }
         }
-        print!("> ");
+    }
+
+    pub fn show_taken_pieces(&self, color: PieceColor, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\n< ")?;
+        match color {
+            PieceColor::Black => self.black_side_information.write_taken_pieces(f)?,
+            PieceColor::White => self.white_side_information.write_taken_pieces(f)?,
+        }
+        write!(f, "> ")
     }
 }
 
 impl fmt::Display for Board {
-    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let black_score = self.black_side_information.total_taken_pieces();
         let white_score = self.white_side_information.total_taken_pieces();
         let score_equal = black_score == white_score;
         let black_winning = black_score > white_score;
-        self.show_taken_pieces(PieceColor::Black);
+        self.show_taken_pieces(PieceColor::Black, f)?;
         if black_winning {
-            println!("+{}", black_score - white_score);
+            writeln!(f, "+{}", black_score - white_score)?;
         }
-        println!("");
-        let mut i = 8;
-        for row in self.rows.iter() {
-            println!("[{} ]{}", i, row);
-            i -= 1;
-            let sleep_time = time::Duration::from_millis(150);
-            thread::sleep(sleep_time);
+        writeln!(f)?;
+        for (index, row) in self.get_rows().iter().enumerate() {
+            writeln!(f, "[{} ]{}", 8 - index, row)?;
         }
-        print!("    ");
+        write!(f, "    ")?;
         for letter in ColumnLetter::iterator() {
-            print!("[{} ]", letter)
+            write!(f, "[{} ]", letter)?;
         }
-        self.show_taken_pieces(PieceColor::White);
+        self.show_taken_pieces(PieceColor::White, f)?;
         if !black_winning && !score_equal {
-            println!("+{}", white_score - black_score);
+            writeln!(f, "+{}", white_score - black_score)?;
         }
-        println!("");
-        Ok(())
+        writeln!(f)
     }
 }
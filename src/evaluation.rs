@@ -0,0 +1,437 @@
+use std::fmt;
+
+use crate::{
+    bitboard::*,
+    board::Board,
+    pieces::{Piece, PieceColor, PieceKind},
+};
+
+//every term is worked out twice, once for the middlegame and once for the endgame, and blended by how much
+//material is left. A score is a (middlegame, endgame) pair in centipawns.
+type TaperedScore = (i32, i32);
+
+//knights and bishops count 1 towards the phase, rooks 2 and queens 4. The starting position adds up to 24.
+const MAX_PHASE: i32 = 24;
+
+const MIDDLEGAME_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+const ENDGAME_VALUES: [i32; 6] = [120, 300, 320, 530, 950, 0];
+
+//the piece square tables are laid out the way white sees the board, with the eighth rank on the top line.
+//They are in PieceKind::index order: pawn, knight, bishop, rook, queen, king.
+#[rustfmt::skip]
+const MIDDLEGAME_TABLES: [[i32; 64]; 6] = [
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+         50,  50,  50,  50,  50,  50,  50,  50,
+         10,  10,  20,  30,  30,  20,  10,  10,
+          5,   5,  10,  25,  25,  10,   5,   5,
+          0,   0,   0,  20,  20,   0,   0,   0,
+          5,  -5, -10,   0,   0, -10,  -5,   5,
+          5,  10,  10, -20, -20,  10,  10,   5,
+          0,   0,   0,   0,   0,   0,   0,   0,
+    ],
+    [
+        -50, -40, -30, -30, -30, -30, -40, -50,
+        -40, -20,   0,   0,   0,   0, -20, -40,
+        -30,   0,  10,  15,  15,  10,   0, -30,
+        -30,   5,  15,  20,  20,  15,   5, -30,
+        -30,   0,  15,  20,  20,  15,   0, -30,
+        -30,   5,  10,  15,  15,  10,   5, -30,
+        -40, -20,   0,   5,   5,   0, -20, -40,
+        -50, -40, -30, -30, -30, -30, -40, -50,
+    ],
+    [
+        -20, -10, -10, -10, -10, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,  10,  10,   5,   0, -10,
+        -10,   5,   5,  10,  10,   5,   5, -10,
+        -10,   0,  10,  10,  10,  10,   0, -10,
+        -10,  10,  10,  10,  10,  10,  10, -10,
+        -10,   5,   0,   0,   0,   0,   5, -10,
+        -20, -10, -10, -10, -10, -10, -10, -20,
+    ],
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+          5,  10,  10,  10,  10,  10,  10,   5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+          0,   0,   0,   5,   5,   0,   0,   0,
+    ],
+    [
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,   5,   5,   5,   0, -10,
+         -5,   0,   5,   5,   5,   5,   0,  -5,
+          0,   0,   5,   5,   5,   5,   0,  -5,
+        -10,   5,   5,   5,   5,   5,   0, -10,
+        -10,   0,   5,   0,   0,   0,   0, -10,
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+    ],
+    [
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -20, -30, -30, -40, -40, -30, -30, -20,
+        -10, -20, -20, -20, -20, -20, -20, -10,
+         20,  20,   0,   0,   0,   0,  20,  20,
+         20,  30,  10,   0,   0,  10,  30,  20,
+    ],
+];
+
+#[rustfmt::skip]
+const ENDGAME_TABLES: [[i32; 64]; 6] = [
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+         80,  80,  80,  80,  80,  80,  80,  80,
+         50,  50,  50,  50,  50,  50,  50,  50,
+         30,  30,  30,  30,  30,  30,  30,  30,
+         20,  20,  20,  20,  20,  20,  20,  20,
+         10,  10,  10,  10,  10,  10,  10,  10,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+    ],
+    [
+        -50, -40, -30, -30, -30, -30, -40, -50,
+        -40, -20,   0,   0,   0,   0, -20, -40,
+        -30,   0,  10,  15,  15,  10,   0, -30,
+        -30,   5,  15,  20,  20,  15,   5, -30,
+        -30,   0,  15,  20,  20,  15,   0, -30,
+        -30,   5,  10,  15,  15,  10,   5, -30,
+        -40, -20,   0,   5,   5,   0, -20, -40,
+        -50, -40, -30, -30, -30, -30, -40, -50,
+    ],
+    [
+        -20, -10, -10, -10, -10, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,  10,  10,   5,   0, -10,
+        -10,   5,   5,  10,  10,   5,   5, -10,
+        -10,   0,  10,  10,  10,  10,   0, -10,
+        -10,  10,  10,  10,  10,  10,  10, -10,
+        -10,   5,   0,   0,   0,   0,   5, -10,
+        -20, -10, -10, -10, -10, -10, -10, -20,
+    ],
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+         10,  10,  10,  10,  10,  10,  10,  10,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+          0,   0,   0,   0,   0,   0,   0,   0,
+    ],
+    [
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+        -10,   0,   5,   5,   5,   5,   0, -10,
+        -10,   5,  10,  10,  10,  10,   5, -10,
+         -5,   5,  10,  15,  15,  10,   5,  -5,
+         -5,   5,  10,  15,  15,  10,   5,  -5,
+        -10,   5,  10,  10,  10,  10,   5, -10,
+        -10,   0,   5,   5,   5,   5,   0, -10,
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+    ],
+    [
+        -50, -40, -30, -20, -20, -30, -40, -50,
+        -30, -20, -10,   0,   0, -10, -20, -30,
+        -30, -10,  20,  30,  30,  20, -10, -30,
+        -30, -10,  30,  40,  40,  30, -10, -30,
+        -30, -10,  30,  40,  40,  30, -10, -30,
+        -30, -10,  20,  30,  30,  20, -10, -30,
+        -30, -30,   0,   0,   0,   0, -30, -30,
+        -50, -30, -30, -30, -30, -30, -30, -50,
+    ],
+];
+
+const DOUBLED_PAWN_PENALTY: TaperedScore = (-10, -20);
+const ISOLATED_PAWN_PENALTY: TaperedScore = (-10, -15);
+//by how many ranks the pawn has moved up from its own back rank.
+const PASSED_PAWN_BONUS: [i32; 8] = [0, 5, 10, 20, 35, 60, 100, 0];
+
+const SHIELD_PAWN_BONUS: [i32; 2] = [10, 5];
+const OPEN_FILE_NEAR_KING_PENALTY: i32 = -15;
+//how dangerous each kind of piece is when it bears down on the squares around the king.
+const KING_ATTACK_WEIGHTS: [i32; 6] = [0, 2, 2, 3, 5, 0];
+const KING_ATTACK_PENALTY: i32 = -4;
+
+//middlegame and endgame centipawns per square a piece can reach, above or below what's usual for it.
+const MOBILITY_WEIGHTS: [TaperedScore; 6] = [(0, 0), (4, 4), (3, 3), (2, 4), (1, 2), (0, 0)];
+const USUAL_MOBILITY: [i32; 6] = [0, 4, 7, 7, 14, 0];
+
+const FILE_A: Bitboard = 0x0101_0101_0101_0101;
+
+fn file_bitboard(file: usize) -> Bitboard {
+    FILE_A << file
+}
+
+fn adjacent_files_bitboard(file: usize) -> Bitboard {
+    let mut adjacent = EMPTY_BITBOARD;
+    if file > 0 {
+        adjacent |= file_bitboard(file - 1);
+    }
+    if file < 7 {
+        adjacent |= file_bitboard(file + 1);
+    }
+    adjacent
+}
+
+//the squares on the ranks in front of a square, from the point of view of the given side.
+fn ranks_in_front(index: usize, color: PieceColor) -> Bitboard {
+    let rank = index / 8;
+    match color {
+        PieceColor::White if rank < 7 => u64::MAX << ((rank + 1) * 8),
+        PieceColor::Black if rank > 0 => u64::MAX >> ((8 - rank) * 8),
+        _ => EMPTY_BITBOARD,
+    }
+}
+
+fn relative_rank(index: usize, color: PieceColor) -> usize {
+    match color {
+        PieceColor::White => index / 8,
+        PieceColor::Black => 7 - index / 8,
+    }
+}
+
+fn table_index(index: usize, color: PieceColor) -> usize {
+    //the tables have a8 first, so white squares flip ranks and black squares are already the right way round.
+    match color {
+        PieceColor::White => (7 - index / 8) * 8 + index % 8,
+        PieceColor::Black => index,
+    }
+}
+
+pub fn piece_value(kind: PieceKind) -> i32 {
+    MIDDLEGAME_VALUES[kind.index()]
+}
+
+pub fn game_phase(board: &Board) -> i32 {
+    let phase = board.kind_bitboard(PieceKind::Knight).count_ones()
+        + board.kind_bitboard(PieceKind::Bishop).count_ones()
+        + board.kind_bitboard(PieceKind::Rook).count_ones() * 2
+        + board.kind_bitboard(PieceKind::Queen).count_ones() * 4;
+    (phase as i32).min(MAX_PHASE)
+}
+
+fn pieces_of(board: &Board, color: PieceColor, kind: PieceKind) -> SquareIndices {
+    square_indices(board.piece_bitboard(Piece { color, kind }))
+}
+
+fn material(board: &Board, color: PieceColor) -> TaperedScore {
+    let mut score = (0, 0);
+    for kind in PieceKind::iterator() {
+        let count = board.count_pieces(Piece { color, kind: *kind });
+        score.0 += MIDDLEGAME_VALUES[kind.index()] * count;
+        score.1 += ENDGAME_VALUES[kind.index()] * count;
+    }
+    score
+}
+
+fn piece_squares(board: &Board, color: PieceColor) -> TaperedScore {
+    let mut score = (0, 0);
+    for kind in PieceKind::iterator() {
+        for index in pieces_of(board, color, *kind) {
+            score.0 += MIDDLEGAME_TABLES[kind.index()][table_index(index, color)];
+            score.1 += ENDGAME_TABLES[kind.index()][table_index(index, color)];
+        }
+    }
+    score
+}
+
+fn pawn_structure(board: &Board, color: PieceColor) -> TaperedScore {
+    let own_pawns = board.piece_bitboard(Piece {
+        color,
+        kind: PieceKind::Pawn,
+    });
+    let opposing_pawns = board.piece_bitboard(Piece {
+        color: color.get_inverse_color(),
+        kind: PieceKind::Pawn,
+    });
+    let mut score = (0, 0);
+
+    for file in 0..8 {
+        let pawns_on_file = (own_pawns & file_bitboard(file)).count_ones() as i32;
+        if pawns_on_file > 1 {
+            score.0 += DOUBLED_PAWN_PENALTY.0 * (pawns_on_file - 1);
+            score.1 += DOUBLED_PAWN_PENALTY.1 * (pawns_on_file - 1);
+        }
+        if pawns_on_file > 0 && own_pawns & adjacent_files_bitboard(file) == EMPTY_BITBOARD {
+            score.0 += ISOLATED_PAWN_PENALTY.0 * pawns_on_file;
+            score.1 += ISOLATED_PAWN_PENALTY.1 * pawns_on_file;
+        }
+    }
+
+    for index in square_indices(own_pawns) {
+        let file = index % 8;
+        let blocking_files = file_bitboard(file) | adjacent_files_bitboard(file);
+        if opposing_pawns & blocking_files & ranks_in_front(index, color) == EMPTY_BITBOARD {
+            let bonus = PASSED_PAWN_BONUS[relative_rank(index, color)];
+            score.0 += bonus / 2;
+            score.1 += bonus;
+        }
+    }
+
+    score
+}
+
+fn king_safety(board: &Board, color: PieceColor) -> TaperedScore {
+    let king_index = match pieces_of(board, color, PieceKind::King).next() {
+        Some(king_index) => king_index,
+        None => return (0, 0),
+    };
+    let own_pawns = board.piece_bitboard(Piece {
+        color,
+        kind: PieceKind::Pawn,
+    });
+    let king_file = king_index % 8;
+    let nearby_files = file_bitboard(king_file) | adjacent_files_bitboard(king_file);
+    let mut score = 0;
+
+    //pawns right in front of the king keep it covered. Files around it with none of our pawns leave it open.
+    for (distance, bonus) in SHIELD_PAWN_BONUS.iter().enumerate() {
+        let shield_rank = match color {
+            PieceColor::White => king_index / 8 + distance + 1,
+            PieceColor::Black => (king_index / 8).wrapping_sub(distance + 1),
+        };
+        if shield_rank < 8 {
+            let shield_squares = nearby_files & (0xFFu64 << (shield_rank * 8));
+            score += bonus * (own_pawns & shield_squares).count_ones() as i32;
+        }
+    }
+    for file in 0..8 {
+        if file_bitboard(file) & nearby_files != EMPTY_BITBOARD
+            && own_pawns & file_bitboard(file) == EMPTY_BITBOARD
+        {
+            score += OPEN_FILE_NEAR_KING_PENALTY;
+        }
+    }
+
+    let king_zone = king_attacks(king_index) | square_bit(king_index);
+    let occupied = board.occupied_bitboard();
+    let attacker_color = color.get_inverse_color();
+    let mut attack_units = 0;
+    for kind in [
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+    ] {
+        for index in pieces_of(board, attacker_color, kind) {
+            let attacked_zone = piece_attacks(kind, index, occupied) & king_zone;
+            attack_units += KING_ATTACK_WEIGHTS[kind.index()] * attacked_zone.count_ones() as i32;
+        }
+    }
+    score += KING_ATTACK_PENALTY * attack_units;
+
+    //once the queens and most of the pieces are gone the king should come out, so this only counts in the middlegame.
+    (score, 0)
+}
+
+fn piece_attacks(kind: PieceKind, index: usize, occupied: Bitboard) -> Bitboard {
+    match kind {
+        PieceKind::Knight => knight_attacks(index),
+        PieceKind::Bishop => bishop_attacks(index, occupied),
+        PieceKind::Rook => rook_attacks(index, occupied),
+        PieceKind::Queen => queen_attacks(index, occupied),
+        PieceKind::King => king_attacks(index),
+        PieceKind::Pawn => EMPTY_BITBOARD,
+    }
+}
+
+fn mobility(board: &Board, color: PieceColor) -> TaperedScore {
+    let occupied = board.occupied_bitboard();
+    let own_pieces = board.color_bitboard(color);
+    //squares the other side's pawns cover aren't really somewhere a piece can go.
+    let mut pawn_covered = EMPTY_BITBOARD;
+    for index in pieces_of(board, color.get_inverse_color(), PieceKind::Pawn) {
+        pawn_covered |= pawn_attacks(index, color.get_inverse_color());
+    }
+
+    let mut score = (0, 0);
+    for kind in [
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+    ] {
+        for index in pieces_of(board, color, kind) {
+            let reachable = piece_attacks(kind, index, occupied) & !own_pieces & !pawn_covered;
+            let extra_squares = reachable.count_ones() as i32 - USUAL_MOBILITY[kind.index()];
+            score.0 += MOBILITY_WEIGHTS[kind.index()].0 * extra_squares;
+            score.1 += MOBILITY_WEIGHTS[kind.index()].1 * extra_squares;
+        }
+    }
+    score
+}
+
+fn taper(score: TaperedScore, phase: i32) -> i32 {
+    (score.0 * phase + score.1 * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+//every term of the evaluation on its own, in centipawns from white's point of view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationBreakdown {
+    pub material: i32,
+    pub piece_squares: i32,
+    pub pawn_structure: i32,
+    pub king_safety: i32,
+    pub mobility: i32,
+    pub phase: i32,
+    pub side_to_move: PieceColor,
+}
+
+impl EvaluationBreakdown {
+    pub fn white_score(&self) -> i32 {
+        self.material + self.piece_squares + self.pawn_structure + self.king_safety + self.mobility
+    }
+
+    pub fn side_to_move_score(&self) -> i32 {
+        match self.side_to_move {
+            PieceColor::White => self.white_score(),
+            PieceColor::Black => -self.white_score(),
+        }
+    }
+}
+
+impl fmt::Display for EvaluationBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pawns = |centipawns: i32| format!("{:+.2}", centipawns as f64 / 100.0);
+        writeln!(f, "(in pawns, plus is good for white)")?;
+        writeln!(f, "material        {:>7}", pawns(self.material))?;
+        writeln!(f, "piece squares   {:>7}", pawns(self.piece_squares))?;
+        writeln!(f, "pawn structure  {:>7}", pawns(self.pawn_structure))?;
+        writeln!(f, "king safety     {:>7}", pawns(self.king_safety))?;
+        writeln!(f, "mobility        {:>7}", pawns(self.mobility))?;
+        writeln!(f, "total           {:>7}", pawns(self.white_score()))?;
+        write!(
+            f,
+            "game phase      {:>7}  (24 is the opening, 0 is a bare endgame)",
+            self.phase
+        )
+    }
+}
+
+pub fn evaluation_breakdown(board: &Board) -> EvaluationBreakdown {
+    let phase = game_phase(board);
+    let term = |side_term: fn(&Board, PieceColor) -> TaperedScore| {
+        let white = side_term(board, PieceColor::White);
+        let black = side_term(board, PieceColor::Black);
+        taper((white.0 - black.0, white.1 - black.1), phase)
+    };
+    EvaluationBreakdown {
+        material: term(material),
+        piece_squares: term(piece_squares),
+        pawn_structure: term(pawn_structure),
+        king_safety: term(king_safety),
+        mobility: term(mobility),
+        phase,
+        side_to_move: board.get_turn(),
+    }
+}
+
+//the score of a position in centipawns, from the point of view of whoever is about to move.
+pub fn evaluate(board: &Board) -> i32 {
+    evaluation_breakdown(board).side_to_move_score()
+}
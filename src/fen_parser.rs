@@ -1,4 +1,4 @@
-use std::{convert, f32::RADIX, fs, path::Path};
+use std::{fmt, fs, path::Path};
 
 use crate::{
     board::{Board, ColumnLetter, Coordinates, Row, SideInformation, Square},
@@ -6,33 +6,149 @@ use crate::{
     pieces::{Piece, PieceColor, PieceKind},
 };
 
-pub fn ingest_fen_file(file_path: &str) -> Option<Board> {
-    let mut board_result: Option<Board> = None;
-
-    if path_exists(file_path) {
-        match digest_filepath_to_string(file_path) {
-            Some(string_result) => {
-                match digest_string_to_board(string_result) {
-                    Some(board) => {
-                        board_result = Some(board);
-                    }
-                    None => {
-                        println!("Oops! Looks like the contents of that file couldn't be parsed correctly.");
-                    }
-                }
-            }
-            None => {
-                println!(
-                    "Oops! looks like we couldn't get a string from {}",
-                    file_path
-                );
-            }
+//positions are character offsets into the fen string, so callers can point right at the problem.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FenError {
+    Unreadable {
+        path: String,
+    },
+    WrongFieldCount {
+        text: String,
+        found: usize,
+    },
+    PiecePlacement {
+        text: String,
+        position: usize,
+        reason: &'static str,
+    },
+    SideToMove {
+        text: String,
+        position: usize,
+    },
+    Castling {
+        text: String,
+        position: usize,
+    },
+    EnPassant {
+        text: String,
+        position: usize,
+    },
+    HalfMove {
+        text: String,
+        position: usize,
+    },
+    FullMove {
+        text: String,
+        position: usize,
+    },
+    //the fields all read fine, but the position they describe couldn't happen.
+    KingCount {
+        color: PieceColor,
+        found: u32,
+    },
+    PawnOnBackRank {
+        square: Coordinates,
+    },
+    SideNotToMoveInCheck {
+        color: PieceColor,
+    },
+    CastlingWithoutKingAndRook {
+        text: String,
+        position: usize,
+    },
+    ImpossibleEnPassant {
+        text: String,
+        position: usize,
+    },
+}
+
+fn color_name(color: &PieceColor) -> &'static str {
+    match color {
+        PieceColor::White => "white",
+        PieceColor::Black => "black",
+    }
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::Unreadable { path } => write!(f, "couldn't read a fen string from {}", path),
+            FenError::WrongFieldCount { text, found } => write!(
+                f,
+                "a fen string has six fields separated by spaces, but \"{}\" has {}",
+                text, found
+            ),
+            FenError::PiecePlacement {
+                text,
+                position,
+                reason,
+            } => write!(
+                f,
+                "bad piece placement \"{}\" at character {}: {}",
+                text, position, reason
+            ),
+            FenError::SideToMove { text, position } => write!(
+                f,
+                "the side to move has to be w or b, not \"{}\" at character {}",
+                text, position
+            ),
+            FenError::Castling { text, position } => write!(
+                f,
+                "bad castling rights \"{}\" at character {}",
+                text, position
+            ),
+            FenError::EnPassant { text, position } => write!(
+                f,
+                "bad en passant square \"{}\" at character {}",
+                text, position
+            ),
+            FenError::HalfMove { text, position } => write!(
+                f,
+                "bad half move clock \"{}\" at character {}",
+                text, position
+            ),
+            FenError::FullMove { text, position } => write!(
+                f,
+                "bad full move number \"{}\" at character {}",
+                text, position
+            ),
+            FenError::KingCount { color, found } => write!(
+                f,
+                "{} needs exactly one king, but there are {}",
+                color_name(color),
+                found
+            ),
+            FenError::PawnOnBackRank { square } => write!(
+                f,
+                "there's a pawn on {}, and pawns can never stand on the first or last rank",
+                square
+            ),
+            FenError::SideNotToMoveInCheck { color } => write!(
+                f,
+                "{} is in check but it's not their move, so the last move left the king hanging",
+                color_name(color)
+            ),
+            FenError::CastlingWithoutKingAndRook { text, position } => write!(
+                f,
+                "castling right {} at character {} needs the king and rook on their starting squares",
+                text, position
+            ),
+            FenError::ImpossibleEnPassant { text, position } => write!(
+                f,
+                "en passant square {} at character {} doesn't follow a pawn's double step",
+                text, position
+            ),
         }
-    } else {
-        println!("It looks like the file {} does exist!", file_path);
     }
+}
 
-    board_result
+pub fn ingest_fen_file(file_path: &str) -> Result<Board, FenError> {
+    match digest_filepath_to_string(file_path) {
+        Some(string_result) => digest_string_to_board(string_result),
+        None => Err(FenError::Unreadable {
+            path: file_path.to_string(),
+        }),
+    }
 }
 
 pub fn path_exists(file_path: &str) -> bool {
@@ -46,107 +162,108 @@ pub fn digest_filepath_to_string(file_path: &str) -> Option<String> {
     }
 }
 
-pub fn digest_string_to_board(file_contents: String) -> Option<Board> {
-    let mut accum_rows: Vec<Row> = Vec::new();
-    let turn_color: PieceColor;
-    let white_castle_kingside: bool;
-    let white_castle_queenside: bool;
-    let black_castle_kingside: bool;
-    let black_castle_queenside: bool;
-    let mut opt_passant_square: Option<Coordinates> = None;
-    let half_turns: u32;
-    let full_turns: u32;
+fn split_fen_fields(fen: &str) -> Vec<(usize, &str)> {
+    //each field along with the offset it starts at.
+    let mut fields: Vec<(usize, &str)> = Vec::new();
+    let mut opt_field_start: Option<usize> = None;
+    for (index, character) in fen.char_indices() {
+        match (character.is_whitespace(), opt_field_start) {
+            (true, Some(field_start)) => {
+                fields.push((field_start, &fen[field_start..index]));
+                opt_field_start = None;
+            }
+            (false, None) => opt_field_start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(field_start) = opt_field_start {
+        fields.push((field_start, &fen[field_start..]));
+    }
+    fields
+}
 
-    let string_parts = file_contents.split(' ').collect::<Vec<&str>>();
+pub fn digest_string_to_board(file_contents: String) -> Result<Board, FenError> {
+    let string_parts = split_fen_fields(&file_contents);
 
     //There must be exactly six parts.
-    if (string_parts.len() != 6) {
-        return None;
+    if string_parts.len() != 6 {
+        return Err(FenError::WrongFieldCount {
+            text: file_contents.trim().to_string(),
+            found: string_parts.len(),
+        });
     }
 
     // the 0th string will always be the rows
-    digest_board_string_into_rows(string_parts.get(0), &mut accum_rows);
+    let (placement_position, placement_string) = string_parts[0];
+    let rows = digest_board_string_into_rows(placement_string, placement_position)?;
 
     //the 1s position string will always be whose turn it is
-    match parse_char_to_turn_color(string_parts.get(1)) {
-        Some(turn) => {
-            turn_color = turn;
-        }
-        None => {
-            return None;
-        }
-    }
+    let (turn_position, turn_string) = string_parts[1];
+    let turn_color = parse_char_to_turn_color(turn_string, turn_position)?;
 
     //the 2nd position string will always be castling rights
-    match string_parts.get(2) {
-        Some(reference_string) => {
-            (
-                white_castle_kingside,
-                white_castle_queenside,
-                black_castle_kingside,
-                black_castle_queenside,
-            ) = parse_string_to_castling_rights(reference_string);
-        }
-        None => {
-            return None;
-        }
-    }
+    let (castling_position, castling_string) = string_parts[2];
+    let castling_rooks = parse_string_to_castling_rights(castling_string, castling_position)?;
 
     //deriving the optional passant square
-    match string_parts.get(3) {
-        Some(passant_string) => {
-            opt_passant_square = parse_string_into_passant_square(passant_string);
-        }
-        None => {
-            return None;
-        }
-    }
+    let (passant_position, passant_string) = string_parts[3];
+    let opt_passant_square = parse_string_into_passant_square(passant_string, passant_position)?;
 
     //get the half turns
-    match string_parts.get(4) {
-        Some(half_turn_string) => match half_turn_string.parse::<u32>() {
-            Ok(half_turns_found) => {
-                half_turns = half_turns_found;
-            }
-            Err(_) => {
-                println!("Could not correctly parse half turns.");
-                return None;
-            }
-        },
-        None => {
-            return None;
+    let (half_turn_position, half_turn_string) = string_parts[4];
+    let half_turns = match half_turn_string.parse::<u32>() {
+        Ok(half_turns_found) => half_turns_found,
+        Err(_) => {
+            return Err(FenError::HalfMove {
+                text: half_turn_string.to_string(),
+                position: half_turn_position,
+            });
         }
-    }
+    };
 
-    //get the full turns
-    match string_parts.get(5) {
-        Some(full_turn_string) => match full_turn_string.parse::<u32>() {
-            Ok(full_turns_found) => {
-                full_turns = full_turns_found;
-            }
-            Err(_) => {
-                println!("Could not correctly parse half turns.");
-                return None;
-            }
-        },
-        None => {
-            return None;
+    //get the full turns. There's no move zero.
+    let (full_turn_position, full_turn_string) = string_parts[5];
+    let full_turns = match full_turn_string.parse::<u32>() {
+        Ok(full_turns_found) if full_turns_found > 0 => full_turns_found,
+        _ => {
+            return Err(FenError::FullMove {
+                text: full_turn_string.to_string(),
+                position: full_turn_position,
+            });
         }
-    }
+    };
 
-    //if we have reached here we can return safely
-    let rows_as_array: [Row; 8] = accum_rows.try_into().expect(
-        "Something appears to have incorrectly converted in moving accumulated rows to an array",
+    //which rook a castling right means depends on where the king is, so lay the pieces out before handing them out.
+    let placement_board = Board::new(
+        rows,
+        turn_color,
+        opt_passant_square,
+        SideInformation::default(PieceColor::White),
+        SideInformation::default(PieceColor::Black),
+        half_turns,
+        full_turns,
     );
 
     let mut white_information = SideInformation::default(PieceColor::White);
-    white_information.set_castling_rights(white_castle_kingside, white_castle_queenside);
+    white_information.set_castling_rights(false, false);
 
     let mut black_information = SideInformation::default(PieceColor::Black);
-    black_information.set_castling_rights(black_castle_kingside, black_castle_queenside);
+    black_information.set_castling_rights(false, false);
+
+    for (color, castling_rook) in castling_rooks {
+        //a right without a rook to go with it gets turned away by validate_position.
+        if let Some((is_kingside, rook_letter)) =
+            find_castling_rook(&placement_board, color, castling_rook)
+        {
+            match color {
+                PieceColor::White => white_information.add_castling_rook(is_kingside, rook_letter),
+                PieceColor::Black => black_information.add_castling_rook(is_kingside, rook_letter),
+            }
+        }
+    }
 
     let mut return_board = Board::new(
-        rows_as_array,
+        rows,
         turn_color,
         opt_passant_square,
         white_information,
@@ -155,81 +272,173 @@ pub fn digest_string_to_board(file_contents: String) -> Option<Board> {
         full_turns,
     );
 
+    validate_position(&return_board, string_parts[2], string_parts[3])?;
+
+    //the kings could be anywhere in an imported position, so go find them.
+    return_board.locate_kings();
+
     //we need to make sure that each side displays the correct number of taken pieces.
     return_board.adjust_taken_pieces();
 
-    Some(return_board)
+    Ok(return_board)
 }
 
-pub fn digest_board_string_into_rows(opt_row_text: Option<&&str>, row_collection: &mut Vec<Row>) {
-    match opt_row_text {
-        Some(row_text) => {
-            let row_strings = row_text.split("/").collect::<Vec<&str>>();
-            if (row_strings.len() != 8) {
-                return; // return early. You should have exactly 8 of these suckers.
+pub fn validate_position(
+    board: &Board,
+    (castling_position, castling_string): (usize, &str),
+    (passant_position, passant_string): (usize, &str),
+) -> Result<(), FenError> {
+    let piece_at = |letter: ColumnLetter, number: isize| {
+        board
+            .retreive_square(&Coordinates::new(letter, number))
+            .unwrap_or(Square::Empty)
+    };
+
+    //everything else leans on there being exactly one king a side, so check that first.
+    for color in PieceColor::iterator() {
+        let king_count = board.count_pieces(Piece {
+            color: *color,
+            kind: PieceKind::King,
+        }) as u32;
+        if king_count != 1 {
+            return Err(FenError::KingCount {
+                color: *color,
+                found: king_count,
+            });
+        }
+    }
+
+    for number in [1, 8] {
+        for letter in ColumnLetter::iterator() {
+            if let Square::Full(piece) = piece_at(*letter, number) {
+                if piece.kind == PieceKind::Pawn {
+                    return Err(FenError::PawnOnBackRank {
+                        square: Coordinates::new(*letter, number),
+                    });
+                }
             }
+        }
+    }
+
+    let waiting_color = board.get_turn().get_inverse_color();
+    if board.is_king_in_danger(waiting_color) {
+        return Err(FenError::SideNotToMoveInCheck {
+            color: waiting_color,
+        });
+    }
 
-            for row_string in row_strings.iter() {
-                match digest_row_string_to_row(row_string) {
-                    Some(row) => {
-                        row_collection.push(row);
-                    }
-                    None => {
-                        // if you failed to add the row, return early.
-                        return;
-                    }
+    if castling_string != "-" {
+        let mut granted_rights: Vec<(PieceColor, bool)> = Vec::new();
+        for (offset, castling_char) in castling_string.char_indices() {
+            let (color, castling_rook) = match parse_castling_char(castling_char) {
+                Some(parsed_char) => parsed_char,
+                None => continue,
+            };
+            match find_castling_rook(board, color, castling_rook) {
+                //K and H both name the same rook in a standard position, so only one of them gets to.
+                Some((is_kingside, _)) if granted_rights.contains(&(color, is_kingside)) => {
+                    return Err(FenError::Castling {
+                        text: castling_string.to_string(),
+                        position: castling_position + offset,
+                    });
+                }
+                Some((is_kingside, _)) => granted_rights.push((color, is_kingside)),
+                None => {
+                    return Err(FenError::CastlingWithoutKingAndRook {
+                        text: castling_char.to_string(),
+                        position: castling_position + offset,
+                    });
                 }
             }
         }
-        None => {
-            //dawg what?
+    }
+
+    if let Some(passant_square) = board.get_opt_passant_square() {
+        //the square a pawn skipped over. The pawn itself sits one further on, and the square it left is now empty.
+        let (mover_color, passant_number, pawn_number, origin_number) = match board.get_turn() {
+            PieceColor::White => (PieceColor::Black, 6, 5, 7),
+            PieceColor::Black => (PieceColor::White, 3, 4, 2),
+        };
+        let passant_possible = passant_square.number == passant_number
+            && piece_at(passant_square.letter, passant_number) == Square::Empty
+            && piece_at(passant_square.letter, origin_number) == Square::Empty
+            && piece_at(passant_square.letter, pawn_number)
+                == Square::Full(Piece {
+                    color: mover_color,
+                    kind: PieceKind::Pawn,
+                });
+        if !passant_possible {
+            return Err(FenError::ImpossibleEnPassant {
+                text: passant_string.to_string(),
+                position: passant_position,
+            });
         }
     }
+
+    Ok(())
 }
 
-pub fn digest_row_string_to_row(row_string: &&str) -> Option<Row> {
+pub fn digest_board_string_into_rows(
+    row_text: &str,
+    position: usize,
+) -> Result<[Row; 8], FenError> {
+    let mut row_collection = [Row::default(); 8];
+    let row_strings = row_text.split('/').collect::<Vec<&str>>();
+    if row_strings.len() != 8 {
+        // You should have exactly 8 of these suckers.
+        return Err(FenError::PiecePlacement {
+            text: row_text.to_string(),
+            position,
+            reason: "there should be exactly eight ranks separated by /",
+        });
+    }
+
+    let mut row_position = position;
+    for (row_index, row_string) in row_strings.iter().enumerate() {
+        row_collection[row_index] = digest_row_string_to_row(row_string, row_position)?;
+        //skip past this rank and the slash after it.
+        row_position += row_string.len() + 1;
+    }
+
+    Ok(row_collection)
+}
+
+pub fn digest_row_string_to_row(row_string: &str, position: usize) -> Result<Row, FenError> {
     let mut squares: Vec<Square> = Vec::new();
+    let row_error = |offset: usize, reason: &'static str| FenError::PiecePlacement {
+        text: row_string.to_string(),
+        position: position + offset,
+        reason,
+    };
 
-    let mut opt_row: Option<Row> = None;
-
-    for character in row_string.chars() {
-        if character.is_numeric() {
-            //this is in base 10. If you want to go look up what a radix is, I recommend
-            //https://doc.rust-lang.org/std/primitive.char.html#method.to_digit
-            match character.to_digit(10) {
-                Some(digit) => {
-                    for _ in 0..digit {
-                        //on christ, I am going to go put a pull request into rust to fix this bullshit.
-                        squares.push(Square::Empty);
-                    }
-                }
-                None => {
-                    //break early. You passed an illegal digit I guess? How?
-                    println!("the character {} was found while trying to parse a fen file for a digit. Sorry.", character);
-                    return None;
+    for (offset, character) in row_string.char_indices() {
+        //this is in base 10. If you want to go look up what a radix is, I recommend
+        //https://doc.rust-lang.org/std/primitive.char.html#method.to_digit
+        match character.to_digit(10) {
+            Some(digit) if (1..=8).contains(&digit) => {
+                for _ in 0..digit {
+                    squares.push(Square::Empty);
                 }
             }
-        } else if character.is_alphabetic() {
-            match parse_char_to_piece(character) {
+            Some(_) => return Err(row_error(offset, "empty squares are counted from 1 to 8")),
+            None => match parse_char_to_piece(character) {
                 Some(piece) => {
                     squares.push(Square::Full(piece));
                 }
                 None => {
-                    return None;
+                    return Err(row_error(offset, "that isn't a piece letter"));
                 }
-            }
+            },
+        }
+        if squares.len() > 8 {
+            return Err(row_error(offset, "this rank has more than eight squares"));
         }
     }
 
-    if (squares.len() == 8) {
-        let squares_array: [Square; 8] = squares.try_into().expect(
-            "Something appears to have converted incorrectly in converting this row to squares",
-        );
-
-        opt_row = Some(Row::new(squares_array));
+    match squares.try_into() {
+        Ok(squares_array) => Ok(Row::new(squares_array)),
+        Err(_) => Err(row_error(0, "this rank has fewer than eight squares")),
     }
-
-    opt_row
 }
 
 pub fn parse_char_to_piecekind(input_char: char) -> Option<PieceKind> {
@@ -244,26 +453,18 @@ pub fn parse_char_to_piecekind(input_char: char) -> Option<PieceKind> {
     }
 }
 
-pub fn parse_char_to_turn_color(opt_turn_char: Option<&&str>) -> Option<PieceColor> {
-    let mut opt_piece_color: Option<PieceColor> = None;
-    match opt_turn_char {
-        Some(turn_string) => {
-            if (turn_string.len() != 1) {
-                return None;
-            }
-
-            for char in turn_string.chars() {
-                match char.to_ascii_lowercase() {
-                    'b' => opt_piece_color = Some(PieceColor::Black),
-                    'w' => opt_piece_color = Some(PieceColor::White),
-                    _ => {}
-                }
-            }
-        }
-        None => {}
+pub fn parse_char_to_turn_color(
+    turn_string: &str,
+    position: usize,
+) -> Result<PieceColor, FenError> {
+    match turn_string.to_ascii_lowercase().as_str() {
+        "w" => Ok(PieceColor::White),
+        "b" => Ok(PieceColor::Black),
+        _ => Err(FenError::SideToMove {
+            text: turn_string.to_string(),
+            position,
+        }),
     }
-
-    opt_piece_color
 }
 
 pub fn parse_char_to_piece(input_char: char) -> Option<Piece> {
@@ -283,80 +484,122 @@ pub fn parse_char_to_piece(input_char: char) -> Option<Piece> {
     }
 }
 
-pub fn parse_string_to_castling_rights(input_str: &&str) -> (bool, bool, bool, bool) {
-    let mut black_kingside = false;
-    let mut black_queenside = false;
-    let mut white_kingside = false;
-    let mut white_queenside = false;
-
-    for character in input_str.chars() {
-        match character {
-            'K' => white_kingside = true,
-            'Q' => white_queenside = true,
-            'k' => black_kingside = true,
-            'q' => black_queenside = true,
-            _ => {
-                //Should I panic here?
-            }
-        }
-    }
+//K and Q mean the outermost rook on that side of the king, the X-FEN way, and a file letter means the rook on that
+//file, the Shredder-FEN way. Chess960 needs the letters when K or Q would be ambiguous.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastlingRook {
+    Kingside,
+    Queenside,
+    File(ColumnLetter),
+}
 
-    (
-        white_kingside,
-        white_queenside,
-        black_kingside,
-        black_queenside,
-    )
+pub fn parse_castling_char(character: char) -> Option<(PieceColor, CastlingRook)> {
+    let color = if character.is_ascii_uppercase() {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    };
+    let castling_rook = match character.to_ascii_lowercase() {
+        'k' => CastlingRook::Kingside,
+        'q' => CastlingRook::Queenside,
+        'a'..='h' => CastlingRook::File(ColumnLetter::convert_to(character).ok()?),
+        _ => return None,
+    };
+    Some((color, castling_rook))
 }
 
-pub fn parse_string_into_passant_square(input_str: &&str) -> Option<Coordinates> {
-    let mut opt_coordinates: Option<Coordinates> = None;
-    let mut opt_col_letter: Option<ColumnLetter> = None;
-    let mut opt_row_number: Option<isize> = None;
+pub fn parse_string_to_castling_rights(
+    input_str: &str,
+    position: usize,
+) -> Result<Vec<(PieceColor, CastlingRook)>, FenError> {
+    let mut castling_rooks: Vec<(PieceColor, CastlingRook)> = Vec::new();
 
-    if input_str.len() > 2 {
-        println!("Encountered an error while attempting to parse the viable en-passant square. String was the wrong size.");
-        return None;
+    if input_str == "-" {
+        return Ok(castling_rooks);
     }
 
-    for char in input_str.chars() {
-        if char.is_alphabetic() {
-            match ColumnLetter::convert_to(char) {
-                Ok(column_letter) => {
-                    opt_col_letter = Some(column_letter);
-                }
-                Err(_) => {
-                    return None;
-                }
-            }
-        } else if char.is_numeric() {
-            //see earlier where we use radix for an explanation
-            match parse_char_to_isize(char) {
-                Ok(size_result) => {
-                    opt_row_number = Some(size_result);
-                }
-                Err(_) => {
-                    println!("Digit was outside the bounds fo the board in parse passant string");
-                    return None;
-                }
-            }
-        } else if char == '-' {
-            return None;
+    for (offset, character) in input_str.char_indices() {
+        let castling_error = FenError::Castling {
+            text: input_str.to_string(),
+            position: position + offset,
+        };
+        let castling_rook = parse_castling_char(character).ok_or(castling_error.clone())?;
+        if castling_rooks.contains(&castling_rook) {
+            //naming the same right twice is a sign something's gone wrong in whatever wrote this.
+            return Err(castling_error);
         }
+        castling_rooks.push(castling_rook);
     }
 
-    match opt_col_letter {
-        Some(col_let) => match opt_row_number {
-            Some(row_num) => {
-                opt_coordinates = Some(Coordinates {
-                    letter: col_let,
-                    number: row_num,
-                });
+    Ok(castling_rooks)
+}
+
+//which side of the king a castling right is for and the file of its rook, or None if that rook or the king isn't on
+//the home row.
+pub fn find_castling_rook(
+    board: &Board,
+    color: PieceColor,
+    castling_rook: CastlingRook,
+) -> Option<(bool, ColumnLetter)> {
+    match castling_rook {
+        CastlingRook::Kingside => board
+            .outermost_rook_letter(color, true)
+            .map(|rook_letter| (true, rook_letter)),
+        CastlingRook::Queenside => board
+            .outermost_rook_letter(color, false)
+            .map(|rook_letter| (false, rook_letter)),
+        CastlingRook::File(rook_letter) => {
+            let home_row: isize = match color {
+                PieceColor::Black => 8,
+                PieceColor::White => 1,
+            };
+            let king_letter = ColumnLetter::iterator().find(|letter| {
+                board.retreive_square(&Coordinates::new(**letter, home_row))
+                    == Ok(Square::Full(Piece {
+                        color,
+                        kind: PieceKind::King,
+                    }))
+            })?;
+            let rook_home = board.retreive_square(&Coordinates::new(rook_letter, home_row))
+                == Ok(Square::Full(Piece {
+                    color,
+                    kind: PieceKind::Rook,
+                }));
+            if rook_home {
+                //a rook to the king's right castles kingside, wherever the two of them are.
+                Some((rook_letter.eval() > king_letter.eval(), rook_letter))
+            } else {
+                None
             }
-            None => {}
-        },
-        None => {}
+        }
     }
+}
 
-    opt_coordinates
+pub fn parse_string_into_passant_square(
+    input_str: &str,
+    position: usize,
+) -> Result<Option<Coordinates>, FenError> {
+    if input_str == "-" {
+        return Ok(None);
+    }
+
+    let passant_error = FenError::EnPassant {
+        text: input_str.to_string(),
+        position,
+    };
+    let passant_chars = input_str.chars().collect::<Vec<char>>();
+    if passant_chars.len() != 2 {
+        return Err(passant_error);
+    }
+
+    match (
+        ColumnLetter::convert_to(passant_chars[0]),
+        parse_char_to_isize(passant_chars[1]),
+    ) {
+        (Ok(col_let), Ok(row_num)) => Ok(Some(Coordinates {
+            letter: col_let,
+            number: row_num,
+        })),
+        _ => Err(passant_error),
+    }
 }
@@ -0,0 +1,469 @@
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board::{Board, MoveResult},
+    moves::Move,
+    pieces::{PieceColor, PieceKind},
+};
+
+//the seven tags every PGN game has to carry, in the order they have to come in.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+//PGN asks for lines no longer than 79 characters in export format.
+const PGN_LINE_LENGTH: usize = 79;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminationReason {
+    Checkmate,
+    Resignation,
+    Stalemate,
+    Repetition,
+    FiftyMove,
+    SeventyFiveMove,
+    InsufficientMaterial,
+    Timeout,
+    Agreement,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub result: GameResult,
+    pub reason: TerminationReason,
+}
+
+impl GameOutcome {
+    pub fn win_for(winner: PieceColor, reason: TerminationReason) -> Self {
+        GameOutcome {
+            result: match winner {
+                PieceColor::White => GameResult::WhiteWins,
+                PieceColor::Black => GameResult::BlackWins,
+            },
+            reason,
+        }
+    }
+
+    pub fn draw(reason: TerminationReason) -> Self {
+        GameOutcome {
+            result: GameResult::Draw,
+            reason,
+        }
+    }
+
+    pub fn winner(&self) -> Option<PieceColor> {
+        match self.result {
+            GameResult::WhiteWins => Some(PieceColor::White),
+            GameResult::BlackWins => Some(PieceColor::Black),
+            GameResult::Draw => None,
+        }
+    }
+
+    pub fn result_tag(&self) -> &'static str {
+        match self.result {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        }
+    }
+
+    //the PGN standard only has a handful of termination values. Everything that ends over the board is "normal".
+    pub fn termination_tag(&self) -> &'static str {
+        match self.reason {
+            TerminationReason::Timeout => "time forfeit",
+            _ => "normal",
+        }
+    }
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason_text = match self.reason {
+            TerminationReason::Checkmate => "by checkmate",
+            TerminationReason::Resignation => "by resignation",
+            TerminationReason::Stalemate => "by stalemate",
+            TerminationReason::Repetition => "by repetition",
+            TerminationReason::FiftyMove => "by the fifty move rule",
+            TerminationReason::SeventyFiveMove => "by the seventy-five move rule",
+            TerminationReason::InsufficientMaterial => "by insufficient material",
+            TerminationReason::Timeout => "on time",
+            TerminationReason::Agreement => "by agreement",
+        };
+        match self.result {
+            GameResult::WhiteWins => write!(f, "White wins {}", reason_text),
+            GameResult::BlackWins => write!(f, "Black wins {}", reason_text),
+            GameResult::Draw => write!(f, "Draw {}", reason_text),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Game {
+    tags: Vec<(String, String)>,
+    starting_board: Board,
+    current_board: Board,
+    moves: Vec<Move>,
+    //the hash key of every position the game has been through, starting position included.
+    position_history: Vec<u64>,
+    opt_outcome: Option<GameOutcome>,
+    //the side with a draw offer on the table, if there is one.
+    opt_draw_offer: Option<PieceColor>,
+    //the index into moves of every move a draw offer was made with.
+    draw_offer_plies: Vec<usize>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Game::from_board(Board::default())
+    }
+
+    pub fn from_board(starting_board: Board) -> Self {
+        let mut game = Game {
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            starting_board: starting_board.clone(),
+            current_board: starting_board.clone(),
+            moves: Vec::new(),
+            position_history: vec![starting_board.hash_key()],
+            opt_outcome: None,
+            opt_draw_offer: None,
+            draw_offer_plies: Vec::new(),
+        };
+
+        //a game that doesn't start from the usual setup has to say where it did start.
+        if starting_board != Board::default() {
+            game.set_tag("SetUp", "1");
+            game.set_tag("FEN", &starting_board.generate_fen_string());
+        }
+
+        game
+    }
+
+    pub fn current_board(&self) -> &Board {
+        &self.current_board
+    }
+
+    pub fn starting_board(&self) -> &Board {
+        &self.starting_board
+    }
+
+    pub fn moves(&self) -> &Vec<Move> {
+        &self.moves
+    }
+
+    //the hash of every position the game has been through, the current one last.
+    pub fn position_history(&self) -> &Vec<u64> {
+        &self.position_history
+    }
+
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.opt_outcome
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.opt_outcome.is_some()
+    }
+
+    fn finish(&mut self, outcome: GameOutcome) {
+        //the first result stands. Nothing that happens after the game is over can change it.
+        if self.is_over() {
+            return;
+        }
+        self.opt_outcome = Some(outcome);
+        self.set_tag("Result", outcome.result_tag());
+        self.set_tag("Termination", outcome.termination_tag());
+    }
+
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn repetition_count(&self) -> usize {
+        let current_key = self.current_board.hash_key();
+        self.position_history
+            .iter()
+            .filter(|position_key| **position_key == current_key)
+            .count()
+    }
+
+    pub fn can_claim_threefold_repetition(&self) -> bool {
+        !self.is_over() && self.repetition_count() >= 3
+    }
+
+    pub fn claim_threefold_repetition(&mut self) -> bool {
+        if self.can_claim_threefold_repetition() {
+            self.finish(GameOutcome::draw(TerminationReason::Repetition));
+            true
+        } else {
+            false
+        }
+    }
+
+    //fifty moves each without a capture or a pawn move. The draw is there to be claimed, it doesn't end the game by itself.
+    pub fn can_claim_fifty_move_rule(&self) -> bool {
+        !self.is_over() && self.current_board.get_half_turns() >= 100
+    }
+
+    pub fn claim_fifty_move_rule(&mut self) -> bool {
+        if self.can_claim_fifty_move_rule() {
+            self.finish(GameOutcome::draw(TerminationReason::FiftyMove));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn resign(&mut self, resigning_color: PieceColor) {
+        self.finish(GameOutcome::win_for(
+            resigning_color.get_inverse_color(),
+            TerminationReason::Resignation,
+        ));
+    }
+
+    pub fn agree_to_draw(&mut self) {
+        self.finish(GameOutcome::draw(TerminationReason::Agreement));
+    }
+
+    pub fn lose_on_time(&mut self, flagged_color: PieceColor) {
+        //running out of time only loses if the other side still has something to mate with besides their king.
+        let opponent_color = flagged_color.get_inverse_color();
+        let opponent_pieces = self.current_board.color_bitboard(opponent_color)
+            & !self.current_board.kind_bitboard(PieceKind::King);
+        if opponent_pieces == 0 {
+            self.finish(GameOutcome::draw(TerminationReason::Timeout));
+        } else {
+            self.finish(GameOutcome::win_for(
+                opponent_color,
+                TerminationReason::Timeout,
+            ));
+        }
+    }
+
+    //the side to move offers the draw, and it goes along with the move they make next.
+    pub fn offer_draw(&mut self) -> bool {
+        if self.is_over() || self.opt_draw_offer.is_some() {
+            false
+        } else {
+            self.opt_draw_offer = Some(self.current_board.get_turn());
+            true
+        }
+    }
+
+    pub fn pending_draw_offer(&self) -> Option<PieceColor> {
+        self.opt_draw_offer
+    }
+
+    //an offer can only be answered by the opponent, once the side that offered has made their move.
+    pub fn can_answer_draw_offer(&self) -> bool {
+        !self.is_over()
+            && self
+                .opt_draw_offer
+                .is_some_and(|offering_color| offering_color != self.current_board.get_turn())
+    }
+
+    pub fn accept_draw(&mut self) -> bool {
+        if self.can_answer_draw_offer() {
+            self.opt_draw_offer = None;
+            self.agree_to_draw();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn decline_draw(&mut self) -> bool {
+        if self.can_answer_draw_offer() {
+            self.opt_draw_offer = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn draw_offer_plies(&self) -> &Vec<usize> {
+        &self.draw_offer_plies
+    }
+
+    pub fn play_move(&mut self, requested_move: &Move) -> MoveResult {
+        //once the game is over the board stays where it ended.
+        if self.is_over() {
+            return MoveResult::MoveIllegal;
+        }
+        let mut move_result = self.current_board.move_piece(requested_move);
+        if !matches!(
+            move_result,
+            MoveResult::WrongTurn | MoveResult::MoveIllegal | MoveResult::EmptySquare
+        ) {
+            self.position_history.push(self.current_board.hash_key());
+            //checkmate still wins even if it happens to repeat a position.
+            if self.repetition_count() >= 5
+                && !matches!(
+                    move_result,
+                    MoveResult::WhiteKingCheckmated | MoveResult::BlackKingCheckmated
+                )
+            {
+                move_result = MoveResult::FivefoldRepetition;
+            }
+        }
+        let opt_outcome = match move_result {
+            MoveResult::WhiteKingCheckmated => Some(GameOutcome::win_for(
+                PieceColor::Black,
+                TerminationReason::Checkmate,
+            )),
+            MoveResult::BlackKingCheckmated => Some(GameOutcome::win_for(
+                PieceColor::White,
+                TerminationReason::Checkmate,
+            )),
+            MoveResult::Stalemate => Some(GameOutcome::draw(TerminationReason::Stalemate)),
+            MoveResult::FivefoldRepetition => {
+                Some(GameOutcome::draw(TerminationReason::Repetition))
+            }
+            MoveResult::InsufficientMaterial => {
+                Some(GameOutcome::draw(TerminationReason::InsufficientMaterial))
+            }
+            MoveResult::SeventyFiveMoveRule => {
+                Some(GameOutcome::draw(TerminationReason::SeventyFiveMove))
+            }
+            MoveResult::CompletedSafely
+            | MoveResult::BlackKingChecked
+            | MoveResult::WhiteKingChecked => None,
+            MoveResult::WrongTurn | MoveResult::MoveIllegal | MoveResult::EmptySquare => {
+                //nothing happened on the board, so there's nothing to write down.
+                return move_result;
+            }
+        };
+        self.moves.push(*requested_move);
+        if let Some(offering_color) = self.opt_draw_offer {
+            if offering_color == self.current_board.get_turn() {
+                //the opponent moved on instead of answering, which turns the offer down.
+                self.opt_draw_offer = None;
+            } else {
+                self.draw_offer_plies.push(self.moves.len() - 1);
+            }
+        }
+        if let Some(outcome) = opt_outcome {
+            self.finish(outcome);
+        }
+        move_result
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, value) in self.tags.iter() {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+        }
+        pgn.push('\n');
+
+        let mut movetext_tokens: Vec<String> = Vec::new();
+        let mut replay_board = self.starting_board.clone();
+        for (ply, played_move) in self.moves.iter().enumerate() {
+            let move_number = replay_board.get_full_turns();
+            match replay_board.get_turn() {
+                PieceColor::White => movetext_tokens.push(format!("{}.", move_number)),
+                PieceColor::Black => {
+                    //a game that starts with black to move still needs a move number in front of the first move.
+                    if ply == 0 {
+                        movetext_tokens.push(format!("{}...", move_number));
+                    }
+                }
+            }
+            movetext_tokens.push(played_move.to_san(&replay_board));
+            replay_board.make_move(played_move);
+            if self.draw_offer_plies.contains(&ply) {
+                movetext_tokens.push("{draw offer}".to_string());
+                //a black move straight after a comment gets its number again.
+                if replay_board.get_turn() == PieceColor::Black && ply + 1 < self.moves.len() {
+                    movetext_tokens.push(format!("{}...", replay_board.get_full_turns()));
+                }
+            }
+        }
+        //the termination tag is too coarse to say how a game ended, so that goes in a comment at the end.
+        if let Some(outcome) = self.opt_outcome {
+            movetext_tokens.push(format!("{{{}}}", outcome));
+        }
+        movetext_tokens.push(self.get_tag("Result").unwrap_or("*").to_string());
+
+        let mut line_length = 0;
+        for token in movetext_tokens.iter() {
+            if line_length > 0 && line_length + 1 + token.len() > PGN_LINE_LENGTH {
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+            pgn.push_str(token);
+            line_length += token.len();
+        }
+        pgn.push_str("\n\n");
+
+        pgn
+    }
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn pgn_date_today() -> String {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => {
+            let (year, month, day) = civil_from_days((since_epoch.as_secs() / 86400) as i64);
+            format!("{:04}.{:02}.{:02}", year, month, day)
+        }
+        Err(_) => "????.??.??".to_string(),
+    }
+}
+
+fn civil_from_days(days_since_epoch: i64) -> (i64, i64, i64) {
+    //Howard Hinnant's days to civil date conversion. Eras are 400 year blocks starting on the 1st of March.
+    let shifted_days = days_since_epoch + 719468;
+    let era = shifted_days.div_euclid(146097);
+    let day_of_era = shifted_days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
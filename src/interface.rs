@@ -1,4 +1,4 @@
-use crate::board::*;
+use crate::{board::*, fen_parser::parse_char_to_piecekind, pieces::PieceKind};
 
 pub fn parse_char_to_isize(row_number: char) -> Result<isize, &'static str> {
     match row_number {
@@ -47,3 +47,23 @@ pub fn parse_square(input_string: &str) -> Result<Coordinates, &'static str> {
         })
     }
 }
+
+pub fn parse_promotion_piece(input_string: &str) -> Result<PieceKind, &'static str> {
+    if input_string.len() != 1 {
+        return Err("incorrect length");
+    }
+
+    match input_string.chars().next() {
+        Some(piece_char) => match parse_char_to_piecekind(piece_char.to_ascii_lowercase()) {
+            Some(piece_kind) => {
+                if piece_kind.is_promotion_choice() {
+                    Ok(piece_kind)
+                } else {
+                    Err("pawns can only promote to a queen, rook, bishop or knight")
+                }
+            }
+            None => Err("not a piece letter"),
+        },
+        None => Err("incorrect length"),
+    }
+}
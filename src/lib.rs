@@ -0,0 +1,35 @@
+//! The rules engine behind Ch-ust. Everything needed to set up a board, check and make moves, and
+//! read or write FEN and PGN, and speak UCI to a chess GUI lives here, so it can be used without the interactive command line interface.
+
+pub mod bitboard;
+pub mod board;
+pub mod evaluation;
+pub mod fen_parser;
+pub mod game;
+pub mod interface;
+pub mod moves;
+pub mod perft;
+pub mod pgn_parser;
+pub mod pieces;
+pub mod polyglot;
+pub mod rules;
+pub mod san;
+pub mod search;
+pub mod tablebase;
+pub mod uci;
+pub mod zobrist;
+
+#[cfg(test)]
+mod perft_tests;
+#[cfg(test)]
+mod unit_tests;
+
+pub use board::{Board, ColumnLetter, Coordinates, MoveResult, Pin, Square, Undo};
+pub use fen_parser::{digest_string_to_board, ingest_fen_file, FenError};
+pub use game::{Game, GameOutcome, GameResult, TerminationReason};
+pub use moves::Move;
+pub use pgn_parser::{read_pgn_file, read_pgn_string, PgnError, PgnReader};
+pub use pieces::{Piece, PieceColor, PieceKind};
+pub use rules::{
+    insufficient_mating_material, king_checkmate_state, parse_move_legality, MateState,
+};
@@ -0,0 +1,38 @@
+use core::fmt;
+
+use crate::{board::Coordinates, pieces::PieceKind};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    pub opt_promotion: Option<PieceKind>,
+}
+
+impl Move {
+    pub fn new(from: Coordinates, to: Coordinates, opt_promotion: Option<PieceKind>) -> Move {
+        Move {
+            from,
+            to,
+            opt_promotion,
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //we print moves the same way people type them into the prompt, e.g. e7e8q
+        write!(
+            f,
+            "{}{}{}{}",
+            self.from.letter.to_string().to_lowercase(),
+            self.from.number,
+            self.to.letter.to_string().to_lowercase(),
+            self.to.number
+        )?;
+        match self.opt_promotion {
+            Some(promotion_kind) => write!(f, "{}", promotion_kind.get_fen_string()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{board::Board, moves::Move};
+
+impl Board {
+    pub fn perft(&self, depth: u32) -> u64 {
+        //we play every line out on one copy and step back with unmake_move, rather than copying the board for each move.
+        let mut search_board = self.clone();
+        search_board.perft_in_place(depth)
+    }
+
+    fn perft_in_place(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let legal_moves = self.legal_moves();
+
+        //on the last ply every legal move is exactly one leaf, so there's no need to go play them.
+        if depth == 1 {
+            return legal_moves.len() as u64;
+        }
+
+        let mut node_count = 0;
+        for legal_move in legal_moves.iter() {
+            let undo = self.make_move(legal_move);
+            node_count += self.perft_in_place(depth - 1);
+            self.unmake_move(undo);
+        }
+
+        node_count
+    }
+
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        let mut divided_counts: Vec<(Move, u64)> = Vec::new();
+
+        if depth == 0 {
+            return divided_counts;
+        }
+
+        let mut search_board = self.clone();
+        for legal_move in self.legal_moves() {
+            let undo = search_board.make_move(&legal_move);
+            divided_counts.push((legal_move, search_board.perft_in_place(depth - 1)));
+            search_board.unmake_move(undo);
+        }
+
+        divided_counts
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{board::Board, fen_parser::digest_string_to_board};
+
+//node counts for these positions are the well known ones from https://www.chessprogramming.org/Perft_Results
+
+fn assert_perft_counts(fen: &str, expected_counts: &[u64]) {
+    let board = digest_string_to_board(fen.to_string()).unwrap();
+
+    for (index, expected_count) in expected_counts.iter().enumerate() {
+        let depth = index as u32 + 1;
+        assert_eq!(
+            board.perft(depth),
+            *expected_count,
+            "perft({}) was wrong for {}",
+            depth,
+            fen
+        );
+    }
+}
+
+#[test]
+fn test_perft_start_position() {
+    assert_perft_counts(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        &[20, 400, 8902, 197281],
+    );
+}
+
+#[test]
+fn test_perft_kiwipete() {
+    assert_perft_counts(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        &[48, 2039, 97862],
+    );
+}
+
+#[test]
+fn test_perft_position_three() {
+    assert_perft_counts(
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        &[14, 191, 2812, 43238],
+    );
+}
+
+#[test]
+fn test_perft_position_four() {
+    assert_perft_counts(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        &[6, 264, 9467],
+    );
+}
+
+#[test]
+fn test_perft_position_four_mirrored() {
+    assert_perft_counts(
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+        &[6, 264, 9467],
+    );
+}
+
+#[test]
+fn test_perft_position_five() {
+    assert_perft_counts(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        &[44, 1486, 62379],
+    );
+}
+
+#[test]
+fn test_perft_position_six() {
+    assert_perft_counts(
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        &[46, 2079, 89890],
+    );
+}
+
+#[test]
+fn test_divide_adds_up_to_perft() {
+    let board = Board::default();
+    let divided_counts = board.divide(2);
+
+    assert_eq!(divided_counts.len(), 20);
+    assert_eq!(
+        divided_counts.iter().map(|(_, count)| count).sum::<u64>(),
+        board.perft(2)
+    );
+}
+
+//Chess960 positions, with the castling rights written the Shredder-FEN way. These come from the wiki's Chess960 perft
+//results page.
+#[test]
+fn test_perft_chess960_positions() {
+    assert_perft_counts(
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        &[21, 528, 12189],
+    );
+    assert_perft_counts(
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        &[21, 807, 18002],
+    );
+    assert_perft_counts(
+        "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+        &[22, 593, 13440],
+    );
+    assert_perft_counts(
+        "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+        &[28, 1120, 31058],
+    );
+}
@@ -0,0 +1,454 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use crate::{board::MoveResult, fen_parser::digest_string_to_board, game::Game};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PgnToken {
+    Tag(String, String),
+    MoveNumber(u32),
+    San(String),
+    Comment(String),
+    Nag(u32),
+    VariationStart,
+    VariationEnd,
+    GameResult(String),
+}
+
+impl fmt::Display for PgnToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PgnToken::Tag(name, value) => write!(f, "[{} \"{}\"]", name, value),
+            PgnToken::MoveNumber(number) => write!(f, "{}.", number),
+            PgnToken::San(san) => write!(f, "{}", san),
+            PgnToken::Comment(comment) => write!(f, "{{{}}}", comment),
+            PgnToken::Nag(nag) => write!(f, "${}", nag),
+            PgnToken::VariationStart => write!(f, "("),
+            PgnToken::VariationEnd => write!(f, ")"),
+            PgnToken::GameResult(result) => write!(f, "{}", result),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PgnError {
+    //the ply we were on when things went wrong. Ply 1 is the first move of the game.
+    pub ply: usize,
+    pub token: String,
+    pub reason: &'static str,
+}
+
+//what the tokenizer hands back when it can't make sense of the text, along with the text in question.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PgnTokenError {
+    pub token: String,
+    pub reason: &'static str,
+}
+
+impl PgnTokenError {
+    fn new(token: String, reason: &'static str) -> Self {
+        PgnTokenError { token, reason }
+    }
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ply {} at \"{}\": {}", self.ply, self.token, self.reason)
+    }
+}
+
+//pulls characters out of a reader one line at a time, so a file never has to be read in all at once.
+struct PgnCharacters<R: BufRead> {
+    reader: R,
+    line: Vec<char>,
+    position: usize,
+}
+
+impl<R: BufRead> PgnCharacters<R> {
+    fn peek(&mut self) -> Option<char> {
+        while self.position >= self.line.len() {
+            let mut next_line = String::new();
+            match self.reader.read_line(&mut next_line) {
+                Ok(0) | Err(_) => return None,
+                Ok(_) => {
+                    //a line starting with % is an escape for other programs, and we skip all of it.
+                    if next_line.starts_with('%') {
+                        next_line = "\n".to_string();
+                    }
+                    self.line = next_line.chars().collect();
+                    self.position = 0;
+                }
+            }
+        }
+        Some(self.line[self.position])
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let opt_next_char = self.peek();
+        if opt_next_char.is_some() {
+            self.position += 1;
+        }
+        opt_next_char
+    }
+}
+
+pub struct PgnTokenizer<R: BufRead> {
+    characters: PgnCharacters<R>,
+}
+
+fn is_symbol_character(symbol_char: char) -> bool {
+    symbol_char.is_ascii_alphanumeric()
+        || matches!(
+            symbol_char,
+            '_' | '+' | '#' | '=' | ':' | '-' | '/' | '!' | '?'
+        )
+}
+
+impl<R: BufRead> PgnTokenizer<R> {
+    pub fn new(reader: R) -> Self {
+        PgnTokenizer {
+            characters: PgnCharacters {
+                reader,
+                line: Vec::new(),
+                position: 0,
+            },
+        }
+    }
+
+    //on running out of file, hands back whatever was read before the end.
+    fn read_until(&mut self, end_char: char) -> Result<String, String> {
+        let mut accum_string = String::new();
+        loop {
+            match self.characters.next() {
+                Some(next_char) if next_char == end_char => return Ok(accum_string),
+                Some(next_char) => accum_string.push(next_char),
+                None => return Err(accum_string),
+            }
+        }
+    }
+
+    fn read_symbol(&mut self, first_char: char) -> String {
+        let mut symbol = first_char.to_string();
+        while let Some(next_char) = self.characters.peek() {
+            if !is_symbol_character(next_char) {
+                break;
+            }
+            symbol.push(next_char);
+            self.characters.next();
+        }
+        symbol
+    }
+
+    fn read_tag(&mut self) -> Result<PgnToken, PgnTokenError> {
+        let mut name = String::new();
+        while let Some(next_char) = self.characters.peek() {
+            if next_char.is_whitespace() || next_char == '"' || next_char == ']' {
+                break;
+            }
+            name.push(next_char);
+            self.characters.next();
+        }
+        while self
+            .characters
+            .peek()
+            .is_some_and(|next_char| next_char.is_whitespace())
+        {
+            self.characters.next();
+        }
+        if name.is_empty() || self.characters.next() != Some('"') {
+            return Err(PgnTokenError::new(
+                format!("[{}", name),
+                "tags need a name and a quoted value",
+            ));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.characters.next() {
+                Some('\\') => match self.characters.next() {
+                    Some(escaped_char) => value.push(escaped_char),
+                    None => break,
+                },
+                Some('"') => {
+                    return match self.read_until(']') {
+                        Ok(leftover) if leftover.trim().is_empty() => {
+                            Ok(PgnToken::Tag(name, value))
+                        }
+                        Ok(leftover) => Err(PgnTokenError::new(
+                            leftover,
+                            "unexpected text after a tag value",
+                        )),
+                        Err(leftover) => Err(PgnTokenError::new(
+                            format!("[{} \"{}\"{}", name, value, leftover),
+                            "the file ended inside a tag",
+                        )),
+                    }
+                }
+                Some(next_char) => value.push(next_char),
+                None => break,
+            }
+        }
+        Err(PgnTokenError::new(
+            format!("[{} \"{}", name, value),
+            "the file ended inside a tag",
+        ))
+    }
+
+    pub fn next_token(&mut self) -> Option<Result<PgnToken, PgnTokenError>> {
+        loop {
+            let next_char = self.characters.next()?;
+            let token = match next_char {
+                whitespace if whitespace.is_whitespace() => continue,
+                '[' => self.read_tag(),
+                '{' => match self.read_until('}') {
+                    Ok(comment) => Ok(PgnToken::Comment(comment)),
+                    Err(comment) => Err(PgnTokenError::new(
+                        format!("{{{}", comment),
+                        "the file ended inside a comment",
+                    )),
+                },
+                ';' => {
+                    //a rest of line comment. The newline might be missing on the last line of a file.
+                    let comment = self.read_until('\n').unwrap_or_else(|comment| comment);
+                    Ok(PgnToken::Comment(comment.trim_end().to_string()))
+                }
+                '(' => Ok(PgnToken::VariationStart),
+                ')' => Ok(PgnToken::VariationEnd),
+                '*' => Ok(PgnToken::GameResult("*".to_string())),
+                '$' => {
+                    let digits = self.read_symbol('$');
+                    match digits[1..].parse::<u32>() {
+                        Ok(nag) => Ok(PgnToken::Nag(nag)),
+                        Err(_) => Err(PgnTokenError::new(
+                            digits,
+                            "a NAG needs a number after the $",
+                        )),
+                    }
+                }
+                '.' => continue, //stray dots, like the ones in "1. ... e5"
+                symbol_start if is_symbol_character(symbol_start) => {
+                    let symbol = self.read_symbol(symbol_start);
+                    match symbol.as_str() {
+                        "1-0" | "0-1" | "1/2-1/2" => Ok(PgnToken::GameResult(symbol)),
+                        _ if symbol
+                            .chars()
+                            .all(|symbol_char| symbol_char.is_ascii_digit()) =>
+                        {
+                            //a move number, with its dots trailing after it.
+                            while self.characters.peek() == Some('.') {
+                                self.characters.next();
+                            }
+                            match symbol.parse::<u32>() {
+                                Ok(move_number) => Ok(PgnToken::MoveNumber(move_number)),
+                                Err(_) => Err(PgnTokenError::new(
+                                    symbol,
+                                    "that move number is far too big",
+                                )),
+                            }
+                        }
+                        _ => Ok(PgnToken::San(symbol)),
+                    }
+                }
+                unexpected_char => Err(PgnTokenError::new(
+                    unexpected_char.to_string(),
+                    "unexpected character in movetext",
+                )),
+            };
+            return Some(token);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnTokenizer<R> {
+    type Item = Result<PgnToken, PgnTokenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+//hands out one game at a time from a PGN source, only reading as far into it as that game goes.
+pub struct PgnReader<R: BufRead> {
+    tokenizer: PgnTokenizer<R>,
+    opt_held_token: Option<PgnToken>,
+    finished: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        PgnReader {
+            tokenizer: PgnTokenizer::new(reader),
+            opt_held_token: None,
+            finished: false,
+        }
+    }
+
+    fn next_game(&mut self) -> Option<Result<Game, PgnError>> {
+        let mut tags: Vec<(String, String)> = Vec::new();
+        let mut opt_game: Option<Game> = None;
+        let mut variation_depth = 0;
+        let mut ply = 1;
+
+        loop {
+            let token = match self.opt_held_token.take() {
+                Some(held_token) => held_token,
+                None => match self.tokenizer.next_token() {
+                    Some(Ok(token)) => token,
+                    Some(Err(token_error)) => {
+                        //only an error that ran into the end of the file finishes the reader off, and skipping
+                        //ahead finds that out for us.
+                        self.skip_to_next_game();
+                        return Some(Err(PgnError {
+                            ply,
+                            token: token_error.token,
+                            reason: token_error.reason,
+                        }));
+                    }
+                    None => {
+                        //a game cut off without a result still counts, as long as there was something in it.
+                        self.finished = true;
+                        if opt_game.is_none() && tags.is_empty() {
+                            return None;
+                        }
+                        return Some(match opt_game {
+                            Some(game) => Ok(game),
+                            None => start_game(&tags, ply),
+                        });
+                    }
+                },
+            };
+
+            if let PgnToken::Tag(name, value) = token {
+                if opt_game.is_some() {
+                    //the tags of the next game. Hang on to this one for next time.
+                    self.opt_held_token = Some(PgnToken::Tag(name, value));
+                    return opt_game.map(Ok);
+                }
+                tags.push((name, value));
+                continue;
+            }
+
+            if opt_game.is_none() {
+                match start_game(&tags, ply) {
+                    Ok(game) => opt_game = Some(game),
+                    Err(pgn_error) => {
+                        self.skip_to_next_game();
+                        return Some(Err(pgn_error));
+                    }
+                }
+            }
+            let game = match opt_game.as_mut() {
+                Some(game) => game,
+                None => continue,
+            };
+
+            match token {
+                PgnToken::VariationStart => variation_depth += 1,
+                PgnToken::VariationEnd => {
+                    if variation_depth == 0 {
+                        self.skip_to_next_game();
+                        return Some(Err(PgnError {
+                            ply,
+                            token: token.to_string(),
+                            reason: "a variation was closed that was never opened",
+                        }));
+                    }
+                    variation_depth -= 1;
+                }
+                PgnToken::San(san) if variation_depth == 0 => {
+                    //variations are skipped over, only the mainline gets played out on the board.
+                    let parsed_move = match game.current_board().parse_san(&san) {
+                        Ok(parsed_move) => parsed_move,
+                        Err(reason) => {
+                            self.skip_to_next_game();
+                            return Some(Err(PgnError {
+                                ply,
+                                token: san,
+                                reason,
+                            }));
+                        }
+                    };
+                    match game.play_move(&parsed_move) {
+                        MoveResult::WrongTurn
+                        | MoveResult::MoveIllegal
+                        | MoveResult::EmptySquare => {
+                            self.skip_to_next_game();
+                            return Some(Err(PgnError {
+                                ply,
+                                token: san,
+                                reason: "the board refused that move",
+                            }));
+                        }
+                        _ => ply += 1,
+                    }
+                }
+                PgnToken::GameResult(result) if variation_depth == 0 => {
+                    game.set_tag("Result", &result);
+                    return opt_game.map(Ok);
+                }
+                _ => {
+                    //comments, NAGs, move numbers and anything inside a variation don't change the mainline.
+                }
+            }
+        }
+    }
+
+    fn skip_to_next_game(&mut self) {
+        //throw away the rest of a broken game, up to where the next one's tags begin.
+        loop {
+            match self.tokenizer.next_token() {
+                Some(Ok(PgnToken::Tag(name, value))) => {
+                    self.opt_held_token = Some(PgnToken::Tag(name, value));
+                    return;
+                }
+                Some(_) => {}
+                None => {
+                    self.finished = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<Game, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished && self.opt_held_token.is_none() {
+            return None;
+        }
+        self.next_game()
+    }
+}
+
+fn start_game(tags: &[(String, String)], ply: usize) -> Result<Game, PgnError> {
+    let mut game = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => match digest_string_to_board(fen.clone()) {
+            Ok(board) => Game::from_board(board),
+            Err(_) => {
+                return Err(PgnError {
+                    ply,
+                    token: fen.clone(),
+                    reason: "the FEN tag couldn't be read",
+                })
+            }
+        },
+        None => Game::new(),
+    };
+    for (name, value) in tags.iter() {
+        game.set_tag(name, value);
+    }
+    Ok(game)
+}
+
+pub fn read_pgn_string(pgn_text: &str) -> PgnReader<&[u8]> {
+    PgnReader::new(pgn_text.as_bytes())
+}
+
+pub fn read_pgn_file(file_path: &str) -> std::io::Result<PgnReader<BufReader<File>>> {
+    Ok(PgnReader::new(BufReader::new(File::open(file_path)?)))
+}
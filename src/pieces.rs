@@ -15,6 +15,13 @@ impl PieceColor {
         }
     }
 
+    pub fn index(&self) -> usize {
+        match self {
+            PieceColor::White => 0,
+            PieceColor::Black => 1,
+        }
+    }
+
     pub fn iterator() -> Iter<'static, PieceColor> {
         //do not widescope this level of letter access. Keep is strictly local
         use PieceColor::*;
@@ -66,6 +73,44 @@ impl PieceKind {
         }
     }
 
+    pub fn index(&self) -> usize {
+        match self {
+            PieceKind::Pawn => 0,
+            PieceKind::Knight => 1,
+            PieceKind::Bishop => 2,
+            PieceKind::Rook => 3,
+            PieceKind::Queen => 4,
+            PieceKind::King => 5,
+        }
+    }
+
+    pub fn iterator() -> Iter<'static, PieceKind> {
+        //do not widescope this level of piece access. Keep is strictly local
+        use PieceKind::*;
+        static KINDS: [PieceKind; 6] = [Pawn, Knight, Bishop, Rook, Queen, King];
+        KINDS.iter()
+    }
+
+    pub fn promotion_choices() -> Iter<'static, PieceKind> {
+        //do not widescope this level of piece access. Keep is strictly local
+        use PieceKind::*;
+        static CHOICES: [PieceKind; 4] = [Queen, Rook, Bishop, Knight];
+        CHOICES.iter()
+    }
+
+    pub fn starting_count(&self) -> i32 {
+        match self {
+            PieceKind::Pawn => 8,
+            PieceKind::Rook | PieceKind::Knight | PieceKind::Bishop => 2,
+            PieceKind::Queen | PieceKind::King => 1,
+        }
+    }
+
+    pub fn is_promotion_choice(&self) -> bool {
+        //a pawn can become anything except another pawn or a king
+        !matches!(self, PieceKind::Pawn | PieceKind::King)
+    }
+
     pub fn get_fen_string(&self) -> String {
         match self {
             PieceKind::Rook => "r".to_string(),
@@ -85,9 +130,8 @@ pub struct Piece {
 }
 
 impl fmt::Display for Piece {
-    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
-        print!("{}{}", self.color, self.kind);
-        Ok(())
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.color, self.kind)
     }
 }
 
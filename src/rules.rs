@@ -1,4 +1,6 @@
+use crate::bitboard::{Bitboard, EMPTY_BITBOARD, LIGHT_SQUARES};
 use crate::board::*;
+use crate::moves::Move;
 use crate::pieces::*;
 
 pub enum MateState {
@@ -20,20 +22,38 @@ pub fn king_checkmate_state(king_color: PieceColor, chess_board: &Board) -> Mate
     }
 }
 
-pub fn would_king_be_in_danger(board: Board, from: &Coordinates, to: &Coordinates) -> bool {
-    let mut copied_board = board.clone();
+//the FIDE dead positions we can spot from material alone: a lone king against a king and at most one minor piece,
+//or nothing but bishops left that all stand on the same color of square. No series of legal moves mates from these.
+pub fn insufficient_mating_material(chess_board: &Board) -> bool {
+    let mating_material = chess_board.kind_bitboard(PieceKind::Pawn)
+        | chess_board.kind_bitboard(PieceKind::Rook)
+        | chess_board.kind_bitboard(PieceKind::Queen);
+    if mating_material != EMPTY_BITBOARD {
+        return false;
+    }
 
-    match board.retreive_square(&from) {
-        Ok(from_square) => match from_square {
-            Square::Full(piece) => {
-                copied_board.set_square(&from, Square::Empty);
-                copied_board.set_square(&to, from_square.clone());
-                copied_board.is_king_in_danger(piece.color)
-            }
-            Square::Empty => {
-                panic!("You tried to move out of an empty square in would_king_be_in_danger!")
-            }
-        },
+    let knights = chess_board.kind_bitboard(PieceKind::Knight);
+    let bishops = chess_board.kind_bitboard(PieceKind::Bishop);
+    let minor_pieces: Bitboard = knights | bishops;
+    if minor_pieces.count_ones() <= 1 {
+        return true;
+    }
+
+    knights == EMPTY_BITBOARD
+        && (bishops & LIGHT_SQUARES == EMPTY_BITBOARD || bishops & !LIGHT_SQUARES == EMPTY_BITBOARD)
+}
+
+pub fn would_king_be_in_danger(board: &Board, from: &Coordinates, to: &Coordinates) -> bool {
+    match board.retreive_square(from) {
+        Ok(Square::Full(piece)) => {
+            //the board holds no heap data, so a copy to play the move out on is cheap.
+            let mut copied_board = board.clone();
+            copied_board.make_move(&Move::new(*from, *to, None));
+            copied_board.is_king_in_danger(piece.color)
+        }
+        Ok(Square::Empty) => {
+            panic!("You tried to move out of an empty square in would_king_be_in_danger!")
+        }
         Err(_) => {
             panic!("You tried to move a piece to an invalid location in would_king_be_in_danger")
         }
@@ -47,9 +67,36 @@ pub fn passant_legal(to: &Coordinates, board: &Board) -> bool {
     }
 }
 
+pub fn promotion_required(from: &Coordinates, to: &Coordinates, chess_board: &Board) -> bool {
+    match chess_board.retreive_square(from) {
+        Ok(Square::Full(piece)) => {
+            piece.kind == PieceKind::Pawn
+                && match piece.color {
+                    PieceColor::White => to.number == 8,
+                    PieceColor::Black => to.number == 1,
+                }
+        }
+        _ => false,
+    }
+}
+
+pub fn probing_promotion(
+    from: &Coordinates,
+    to: &Coordinates,
+    chess_board: &Board,
+) -> Option<PieceKind> {
+    //when we only want to know if a square can be reached, any promotion piece will do.
+    if promotion_required(from, to, chess_board) {
+        Some(PieceKind::Queen)
+    } else {
+        None
+    }
+}
+
 pub fn parse_move_legality(
     from: &Coordinates,
     to: &Coordinates,
+    opt_promotion: Option<PieceKind>,
     chess_board: &Board,
 ) -> (
     bool,
@@ -72,6 +119,23 @@ pub fn parse_move_legality(
     let mut opt_passant_removal = None;
     let mut opt_passant_target: Option<Coordinates> = None;
 
+    //castling gets settled by the board, since in Chess960 the king can even step onto its own rook to do it.
+    if let (Ok(Square::Full(king)), Some(is_kingside)) = (
+        opt_from_square,
+        chess_board.castling_side(&Move::new(*from, *to, opt_promotion)),
+    ) {
+        return (
+            chess_board.king_can_castle(king.color, is_kingside),
+            false,
+            king.color,
+            PieceKind::King,
+            move_information.move_direction,
+            move_information.distance,
+            None,
+            None,
+        );
+    }
+
     //we can skip a lot of work by just checking that from and to are valid locations
     match opt_from_square {
         Ok(from_square) => {
@@ -179,9 +243,22 @@ pub fn parse_move_legality(
                                                                 }
                                                             }
                                                         }
-                                                        MoveDirection::Diagonal(_) => {
-                                                            successful =
-                                                                passant_legal(to, chess_board);
+                                                        MoveDirection::Diagonal(diagonal) => {
+                                                            //pawns only ever take forwards, even en passant.
+                                                            let forwards = match from_piece.color {
+                                                                PieceColor::Black => matches!(
+                                                                    diagonal,
+                                                                    DiagonalDirection::DownLeft
+                                                                        | DiagonalDirection::DownRight
+                                                                ),
+                                                                PieceColor::White => matches!(
+                                                                    diagonal,
+                                                                    DiagonalDirection::UpLeft
+                                                                        | DiagonalDirection::UpRight
+                                                                ),
+                                                            };
+                                                            successful = forwards
+                                                                && passant_legal(to, chess_board);
 
                                                             if successful {
                                                                 taking_piece = true;
@@ -232,23 +309,11 @@ pub fn parse_move_legality(
                                                 successful = true;
                                             } else if move_information.distance == 2 {
                                                 match move_information.move_direction {
-                                                    MoveDirection::Left => {
-                                                        if chess_board.king_can_castle(
-                                                            from_piece.color,
-                                                            false,
-                                                        ) {
-                                                            //ok, we now need to do an additional check to see if castling the king on this side would be safe.
-                                                            successful = true;
-                                                        }
-                                                    }
-                                                    MoveDirection::Right => {
-                                                        if chess_board
-                                                            .king_can_castle(from_piece.color, true)
-                                                        {
-                                                            successful = true;
-                                                        }
-                                                    }
-                                                    MoveDirection::Up | MoveDirection::Down => (),
+                                                    //castling was already dealt with up top.
+                                                    MoveDirection::Left
+                                                    | MoveDirection::Right
+                                                    | MoveDirection::Up
+                                                    | MoveDirection::Down => (),
                                                     _ => {
                                                         //for any of the other diagonal moves we allow this move distance
                                                         successful = true;
@@ -272,8 +337,17 @@ pub fn parse_move_legality(
                             _ => (),
                         };
 
-                        successful =
-                            successful && !would_king_be_in_danger(chess_board.clone(), from, to);
+                        //a pawn reaching the last rank has to name what it becomes, and nothing else gets to promote.
+                        let promotion_needed = promotion_required(from, to, chess_board);
+                        successful = successful
+                            && match opt_promotion {
+                                Some(promotion_kind) => {
+                                    promotion_needed && promotion_kind.is_promotion_choice()
+                                }
+                                None => !promotion_needed,
+                            };
+
+                        successful = successful && !would_king_be_in_danger(chess_board, from, to);
                     }
                 }
                 Err(_) => {
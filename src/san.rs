@@ -0,0 +1,205 @@
+use crate::{
+    board::{Board, ColumnLetter, Coordinates, Square},
+    interface::parse_char_to_isize,
+    moves::Move,
+    pieces::PieceKind,
+};
+
+fn san_square(coords: &Coordinates) -> String {
+    format!(
+        "{}{}",
+        coords.letter.to_string().to_lowercase(),
+        coords.number
+    )
+}
+
+fn is_castling_move(chess_move: &Move, board: &Board) -> bool {
+    board.castling_side(chess_move).is_some()
+}
+
+fn parse_san_piece_letter(piece_char: char) -> Option<PieceKind> {
+    //piece letters are always uppercase in SAN. A lowercase b is the b file, not a bishop.
+    match piece_char {
+        'N' => Some(PieceKind::Knight),
+        'B' => Some(PieceKind::Bishop),
+        'R' => Some(PieceKind::Rook),
+        'Q' => Some(PieceKind::Queen),
+        'K' => Some(PieceKind::King),
+        _ => None,
+    }
+}
+
+impl Move {
+    pub fn to_san(&self, board: &Board) -> String {
+        let mut san = String::new();
+        let moving_piece = match board.retreive_square(&self.from) {
+            Ok(Square::Full(piece)) => piece,
+            _ => {
+                panic!(
+                    "attempted to write SAN for a move from an empty square at {}",
+                    self.from
+                )
+            }
+        };
+
+        if let Some(is_kingside) = board.castling_side(self) {
+            if is_kingside {
+                san.push_str("O-O");
+            } else {
+                san.push_str("O-O-O");
+            }
+        } else {
+            let is_capture = match board.retreive_square(&self.to) {
+                Ok(Square::Full(_)) => true,
+                //a pawn can only move sideways onto an empty square by taking en passant.
+                _ => moving_piece.kind == PieceKind::Pawn && self.from.letter != self.to.letter,
+            };
+
+            if moving_piece.kind == PieceKind::Pawn {
+                if is_capture {
+                    san.push_str(&self.from.letter.to_string().to_lowercase());
+                }
+            } else {
+                san.push_str(&moving_piece.kind.to_string());
+
+                //only name as much of the starting square as it takes to tell this piece apart from its twins.
+                let twins = board
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|other_move| {
+                        other_move.to == self.to
+                            && other_move.from != self.from
+                            && board.retreive_square(&other_move.from)
+                                == Ok(Square::Full(moving_piece))
+                    })
+                    .collect::<Vec<Move>>();
+                if !twins.is_empty() {
+                    let letter_shared = twins
+                        .iter()
+                        .any(|twin| twin.from.letter == self.from.letter);
+                    let number_shared = twins
+                        .iter()
+                        .any(|twin| twin.from.number == self.from.number);
+                    if !letter_shared {
+                        san.push_str(&self.from.letter.to_string().to_lowercase());
+                    } else if !number_shared {
+                        san.push_str(&self.from.number.to_string());
+                    } else {
+                        san.push_str(&san_square(&self.from));
+                    }
+                }
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&san_square(&self.to));
+
+            if let Some(promotion_kind) = self.opt_promotion {
+                san.push('=');
+                san.push_str(&promotion_kind.to_string());
+            }
+        }
+
+        let mut next_board = board.clone();
+        next_board.make_move(self);
+        let opponent_color = moving_piece.color.get_inverse_color();
+        if next_board.is_king_in_danger(opponent_color) {
+            if next_board.legal_move_available() {
+                san.push('+');
+            } else {
+                san.push('#');
+            }
+        }
+
+        san
+    }
+}
+
+impl Board {
+    pub fn parse_san(&self, san_input: &str) -> Result<Move, &'static str> {
+        //check, mate and annotation marks don't change which move is meant, so we can drop them.
+        let san = san_input.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        if san.is_empty() {
+            return Err("no move given");
+        }
+
+        let legal_moves = self.legal_moves();
+
+        //people type castling with zeroes about as often as with the letter O.
+        let opt_castling_side = match san {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(is_kingside) = opt_castling_side {
+            return legal_moves
+                .into_iter()
+                .find(|legal_move| self.castling_side(legal_move) == Some(is_kingside))
+                .ok_or("castling isn't legal here");
+        }
+
+        let mut san_chars = san.chars().collect::<Vec<char>>();
+
+        let piece_kind = match parse_san_piece_letter(san_chars[0]) {
+            Some(piece_kind) => {
+                san_chars.remove(0);
+                piece_kind
+            }
+            None => PieceKind::Pawn,
+        };
+
+        //promotions can come as e8=Q or e8Q.
+        let mut opt_promotion = None;
+        if let Some(last_char) = san_chars.last() {
+            if let Some(promotion_kind) = parse_san_piece_letter(*last_char) {
+                if !promotion_kind.is_promotion_choice() {
+                    return Err("pawns can only promote to a queen, rook, bishop or knight");
+                }
+                opt_promotion = Some(promotion_kind);
+                san_chars.pop();
+                if san_chars.last() == Some(&'=') {
+                    san_chars.pop();
+                }
+            }
+        }
+
+        if san_chars.len() < 2 {
+            return Err("a move needs a target square");
+        }
+        let target_number = parse_char_to_isize(san_chars[san_chars.len() - 1])?;
+        let target_letter = ColumnLetter::convert_to(san_chars[san_chars.len() - 2])?;
+        let target = Coordinates::new(target_letter, target_number);
+
+        //whatever is left over is the disambiguation, with an optional capture mark after it.
+        let mut opt_from_letter = None;
+        let mut opt_from_number = None;
+        for hint_char in san_chars[..san_chars.len() - 2].iter() {
+            match hint_char {
+                'x' | ':' => {}
+                '1'..='8' => opt_from_number = Some(parse_char_to_isize(*hint_char)?),
+                'a'..='h' => opt_from_letter = Some(ColumnLetter::convert_to(*hint_char)?),
+                _ => return Err("unexpected character in move"),
+            }
+        }
+
+        let mut candidates = legal_moves.into_iter().filter(|legal_move| {
+            legal_move.to == target
+                && legal_move.opt_promotion == opt_promotion
+                && !is_castling_move(legal_move, self)
+                && opt_from_letter.is_none_or(|letter| legal_move.from.letter == letter)
+                && opt_from_number.is_none_or(|number| legal_move.from.number == number)
+                && match self.retreive_square(&legal_move.from) {
+                    Ok(Square::Full(piece)) => piece.kind == piece_kind,
+                    _ => false,
+                }
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(found_move), None) => Ok(found_move),
+            (Some(_), Some(_)) => Err("that move is ambiguous"),
+            (None, _) => Err("no legal move matches"),
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    board::{Board, Square},
+    evaluation::{evaluate, piece_value},
+    moves::Move,
+    pieces::PieceKind,
+    rules::insufficient_mating_material,
+};
+
+//scores are in centipawns from the side to move's point of view. A mate is worth more than any amount of material,
+//and a nearer mate is worth more than one further away.
+pub const MATE_SCORE: i32 = 100_000;
+const INFINITY: i32 = MATE_SCORE + 1;
+pub const MAX_SEARCH_DEPTH: u32 = 64;
+
+//how many nodes go by between looks at the clock.
+const NODES_BETWEEN_TIME_CHECKS: u64 = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub opt_depth: Option<u32>,
+    pub opt_time: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    pub opt_best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+    pub principal_variation: Vec<Move>,
+}
+
+impl SearchResult {
+    //how many moves until mate, counting only the mating side's moves. Negative when we're the ones getting mated.
+    pub fn opt_mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE_SCORE - MAX_SEARCH_DEPTH as i32 * 2 {
+            return None;
+        }
+        let plies = MATE_SCORE - self.score.abs();
+        let moves = (plies + 1) / 2;
+        Some(if self.score > 0 { moves } else { -moves })
+    }
+}
+
+//the piece a move takes, if it takes one. En passant takes a pawn from a square the move doesn't land on.
+fn captured_kind(board: &Board, chess_move: &Move) -> Option<PieceKind> {
+    //a Chess960 castle can have the king land on its own rook, which takes nothing.
+    if board.castling_side(chess_move).is_some() {
+        return None;
+    }
+    match board.retreive_square(&chess_move.to) {
+        Ok(Square::Full(piece)) => Some(piece.kind),
+        _ => match board.retreive_square(&chess_move.from) {
+            Ok(Square::Full(piece))
+                if piece.kind == PieceKind::Pawn
+                    && chess_move.from.letter != chess_move.to.letter =>
+            {
+                Some(PieceKind::Pawn)
+            }
+            _ => None,
+        },
+    }
+}
+
+fn moving_kind(board: &Board, chess_move: &Move) -> PieceKind {
+    match board.retreive_square(&chess_move.from) {
+        Ok(Square::Full(piece)) => piece.kind,
+        _ => {
+            panic!(
+                "attempted to order a move from an empty square at {}",
+                chess_move.from
+            )
+        }
+    }
+}
+
+struct Searcher<'a> {
+    //two quiet moves per ply that caused a cutoff last time we were at that depth. They're worth trying early.
+    killer_moves: Vec<[Option<Move>; 2]>,
+    //the hash of every position on the current line, so we can spot a repetition.
+    line_hashes: Vec<u64>,
+    previous_principal_variation: Vec<Move>,
+    nodes: u64,
+    opt_deadline: Option<Instant>,
+    opt_stop_signal: Option<&'a AtomicBool>,
+    stopped: bool,
+}
+
+impl Searcher<'_> {
+    fn out_of_time(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+        if self.nodes.is_multiple_of(NODES_BETWEEN_TIME_CHECKS) {
+            let past_deadline = self
+                .opt_deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+            let told_to_stop = self
+                .opt_stop_signal
+                .is_some_and(|stop_signal| stop_signal.load(Ordering::Relaxed));
+            self.stopped = past_deadline || told_to_stop;
+        }
+        self.stopped
+    }
+
+    fn order_moves(&self, board: &Board, moves: &mut [Move], ply: usize) {
+        let opt_pv_move = self.previous_principal_variation.get(ply).copied();
+        let killers = self.killer_moves[ply];
+        moves.sort_by_cached_key(|chess_move| {
+            //lower sorts first. The last line's best move, then captures by MVV-LVA, then killers, then the rest.
+            if Some(*chess_move) == opt_pv_move {
+                return i32::MIN;
+            }
+            if let Some(victim_kind) = captured_kind(board, chess_move) {
+                return -1_000_000 - piece_value(victim_kind) * 10
+                    + piece_value(moving_kind(board, chess_move)) / 10;
+            }
+            if chess_move.opt_promotion.is_some() {
+                return -900_000;
+            }
+            if killers.contains(&Some(*chess_move)) {
+                return -500_000;
+            }
+            0
+        });
+    }
+
+    fn store_killer(&mut self, chess_move: Move, ply: usize) {
+        let killers = &mut self.killer_moves[ply];
+        if killers[0] != Some(chess_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(chess_move);
+        }
+    }
+
+    fn is_draw(&self, board: &Board) -> bool {
+        let current_hash = board.hash_key();
+        board.get_half_turns() >= 100
+            || insufficient_mating_material(board)
+            || self.line_hashes[..self.line_hashes.len() - 1]
+                .iter()
+                .rev()
+                .take(board.get_half_turns() as usize)
+                .any(|line_hash| *line_hash == current_hash)
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        principal_variation: &mut Vec<Move>,
+    ) -> i32 {
+        principal_variation.clear();
+        self.nodes += 1;
+        if ply > 0 && self.out_of_time() {
+            return 0;
+        }
+
+        //mate and stalemate come first. A mate delivered on the 100th half move still counts as a mate.
+        let mut legal_moves = board.legal_moves();
+        if legal_moves.is_empty() {
+            return if board.is_king_in_danger(board.get_turn()) {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+        if ply > 0 && self.is_draw(board) {
+            return 0;
+        }
+        if depth == 0 || ply >= MAX_SEARCH_DEPTH as usize {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        self.order_moves(board, &mut legal_moves, ply);
+        let mut child_variation: Vec<Move> = Vec::new();
+        for legal_move in legal_moves {
+            let is_quiet = captured_kind(board, &legal_move).is_none();
+            let undo = board.make_move(&legal_move);
+            self.line_hashes.push(board.hash_key());
+            let score = -self.negamax(
+                board,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_variation,
+            );
+            self.line_hashes.pop();
+            board.unmake_move(undo);
+
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                if is_quiet {
+                    self.store_killer(legal_move, ply);
+                }
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                principal_variation.clear();
+                principal_variation.push(legal_move);
+                principal_variation.extend_from_slice(&child_variation);
+            }
+        }
+
+        alpha
+    }
+
+    //only captures and promotions, so we never stop looking in the middle of a trade. In check there's no
+    //standing pat though, every way out of the check gets looked at.
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            return 0;
+        }
+
+        let in_check = board.is_king_in_danger(board.get_turn());
+        let legal_moves = board.legal_moves();
+        if in_check && legal_moves.is_empty() {
+            return -MATE_SCORE + ply as i32;
+        }
+
+        if !in_check {
+            let standing_score = evaluate(board);
+            if standing_score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(standing_score);
+        }
+
+        let mut noisy_moves = legal_moves
+            .into_iter()
+            .filter(|legal_move| {
+                in_check
+                    || legal_move.opt_promotion.is_some()
+                    || captured_kind(board, legal_move).is_some()
+            })
+            .collect::<Vec<Move>>();
+        self.order_moves(board, &mut noisy_moves, ply.min(MAX_SEARCH_DEPTH as usize));
+
+        for noisy_move in noisy_moves {
+            let undo = board.make_move(&noisy_move);
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            board.unmake_move(undo);
+
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+}
+
+pub fn search(board: &Board, limits: SearchLimits) -> SearchResult {
+    search_with_progress(board, limits, None, |_| {})
+}
+
+//for searching in the middle of a game. position_history is the hash of every position played so far, the current
+//one last, so a line that goes back to one of them gets scored as the draw it would be.
+pub fn search_with_history(
+    board: &Board,
+    position_history: &[u64],
+    limits: SearchLimits,
+) -> SearchResult {
+    search_with_history_and_progress(board, position_history, limits, None, |_| {})
+}
+
+//iterative deepening. Each finished depth is handed to on_iteration, so a caller can report progress as it goes.
+//Setting the stop signal ends the search early with the deepest finished result.
+pub fn search_with_progress(
+    board: &Board,
+    limits: SearchLimits,
+    opt_stop_signal: Option<&AtomicBool>,
+    on_iteration: impl FnMut(&SearchResult),
+) -> SearchResult {
+    search_with_history_and_progress(board, &[], limits, opt_stop_signal, on_iteration)
+}
+
+//both of the above at once, for a GUI that sends the moves of the game so far and can stop us at any time.
+pub fn search_with_history_and_progress(
+    board: &Board,
+    position_history: &[u64],
+    limits: SearchLimits,
+    opt_stop_signal: Option<&AtomicBool>,
+    mut on_iteration: impl FnMut(&SearchResult),
+) -> SearchResult {
+    let mut line_hashes = position_history.to_vec();
+    if line_hashes.last() != Some(&board.hash_key()) {
+        line_hashes.push(board.hash_key());
+    }
+
+    let start = Instant::now();
+    let max_depth = limits
+        .opt_depth
+        .unwrap_or(MAX_SEARCH_DEPTH)
+        .clamp(1, MAX_SEARCH_DEPTH);
+    let mut searcher = Searcher {
+        //quiescence can run past the depth limit, so the killer table has room for a line twice as long.
+        killer_moves: vec![[None, None]; MAX_SEARCH_DEPTH as usize * 2 + 1],
+        line_hashes,
+        previous_principal_variation: Vec::new(),
+        nodes: 0,
+        opt_deadline: None,
+        opt_stop_signal,
+        stopped: false,
+    };
+
+    //whatever happens, there's always a legal move to hand back if one exists.
+    let mut best_result = SearchResult {
+        opt_best_move: board.legal_moves().first().copied(),
+        score: 0,
+        depth: 0,
+        nodes: 0,
+        principal_variation: Vec::new(),
+    };
+    if best_result.opt_best_move.is_none() {
+        return best_result;
+    }
+
+    let mut search_board = board.clone();
+    for depth in 1..=max_depth {
+        //the first depth always gets to finish, so there's a real answer even with almost no time.
+        if depth == 2 {
+            searcher.opt_deadline = limits.opt_time.map(|time| start + time);
+        }
+        let mut principal_variation: Vec<Move> = Vec::new();
+        let score = searcher.negamax(
+            &mut search_board,
+            depth,
+            0,
+            -INFINITY,
+            INFINITY,
+            &mut principal_variation,
+        );
+        if searcher.stopped {
+            break;
+        }
+
+        best_result = SearchResult {
+            opt_best_move: principal_variation
+                .first()
+                .copied()
+                .or(best_result.opt_best_move),
+            score,
+            depth,
+            nodes: searcher.nodes,
+            principal_variation: principal_variation.clone(),
+        };
+        on_iteration(&best_result);
+        searcher.previous_principal_variation = principal_variation;
+
+        //no point looking deeper once we've found a forced mate.
+        if best_result.opt_mate_in().is_some() {
+            break;
+        }
+        if limits.opt_time.is_some_and(|time| start.elapsed() >= time) {
+            break;
+        }
+    }
+
+    best_result.nodes = searcher.nodes;
+    best_result
+}
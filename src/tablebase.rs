@@ -0,0 +1,1378 @@
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt, fs, io,
+    path::PathBuf,
+    rc::Rc,
+    sync::OnceLock,
+};
+
+use crate::{
+    bitboard::{coordinates_from_index, king_attacks, square_bit, square_indices},
+    board::{Board, Square},
+    moves::Move,
+    pieces::{Piece, PieceColor, PieceKind},
+};
+
+//Syzygy tables cover every position with up to seven pieces, kings included. The layout of the files follows the
+//format Ronald de Man's generator writes, and the probing follows the same steps the engines that read them use.
+pub const MAX_TABLEBASE_PIECES: usize = 7;
+
+const WDL_SUFFIX: &str = ".rtbw";
+const DTZ_SUFFIX: &str = ".rtbz";
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+//flags at the top of a file.
+const SPLIT_FLAG: u8 = 1;
+const HAS_PAWNS_FLAG: u8 = 2;
+//flags at the top of each block of compressed positions.
+const STM_FLAG: u8 = 1;
+const MAPPED_FLAG: u8 = 2;
+const WIN_PLIES_FLAG: u8 = 4;
+const LOSS_PLIES_FLAG: u8 = 8;
+const WIDE_FLAG: u8 = 16;
+const SINGLE_VALUE_FLAG: u8 = 128;
+
+const CORRUPT_TABLE: &str = "a tablebase file is corrupt";
+
+//win, draw or loss for the side to move. The cursed and blessed results are wins and losses that the fifty move
+//rule turns into draws.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl fmt::Display for Wdl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Wdl::Loss => write!(f, "loss"),
+            Wdl::BlessedLoss => write!(f, "loss, but drawn by the fifty move rule"),
+            Wdl::Draw => write!(f, "draw"),
+            Wdl::CursedWin => write!(f, "win, but drawn by the fifty move rule"),
+            Wdl::Win => write!(f, "win"),
+        }
+    }
+}
+
+impl Wdl {
+    fn from_value(value: i32) -> Wdl {
+        match value {
+            i32::MIN..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    //-2 for a loss up to 2 for a win, the way the tables store it.
+    pub fn value(&self) -> i32 {
+        match self {
+            Wdl::Loss => -2,
+            Wdl::BlessedLoss => -1,
+            Wdl::Draw => 0,
+            Wdl::CursedWin => 1,
+            Wdl::Win => 2,
+        }
+    }
+
+    //the same result seen from the other side of the board.
+    pub fn opposite(&self) -> Wdl {
+        Wdl::from_value(-self.value())
+    }
+
+    //what the distance to zero was just before a capture or pawn move that reached this result.
+    fn dtz_before_zeroing(&self) -> i32 {
+        match self {
+            Wdl::Loss => -1,
+            Wdl::BlessedLoss => -101,
+            Wdl::Draw => 0,
+            Wdl::CursedWin => 101,
+            Wdl::Win => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TablebaseMove {
+    pub chess_move: Move,
+    //the result and distance to zero after the move, from the point of view of the side making it.
+    pub wdl: Wdl,
+    pub dtz: i32,
+}
+
+//the lookup tables that turn a placement of pieces into a position's number within a table. They're the same for
+//every table, so they're worked out once.
+struct IndexMaps {
+    map_pawns: [usize; 64],
+    map_b1h1h7: [usize; 64],
+    map_a1d1d4: [usize; 64],
+    map_kk: [[usize; 64]; 10],
+    binomial: [[u64; 64]; 6],
+    lead_pawn_index: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn file_of(square: usize) -> usize {
+    square % 8
+}
+
+fn rank_of(square: usize) -> usize {
+    square / 8
+}
+
+//how far a square is above the a1-h8 diagonal. Negative means below it.
+fn off_diagonal(square: usize) -> isize {
+    rank_of(square) as isize - file_of(square) as isize
+}
+
+fn flip_file(square: usize) -> usize {
+    square ^ 7
+}
+
+fn flip_rank(square: usize) -> usize {
+    square ^ 56
+}
+
+fn index_maps() -> &'static IndexMaps {
+    static INDEX_MAPS: OnceLock<IndexMaps> = OnceLock::new();
+    INDEX_MAPS.get_or_init(build_index_maps)
+}
+
+fn build_index_maps() -> IndexMaps {
+    let mut maps = IndexMaps {
+        map_pawns: [0; 64],
+        map_b1h1h7: [0; 64],
+        map_a1d1d4: [0; 64],
+        map_kk: [[0; 64]; 10],
+        binomial: [[0; 64]; 6],
+        lead_pawn_index: [[0; 64]; 6],
+        lead_pawns_size: [[0; 4]; 6],
+    };
+
+    //the 28 squares below the a1-h8 diagonal.
+    let mut code = 0;
+    for square in 0..64 {
+        if off_diagonal(square) < 0 {
+            maps.map_b1h1h7[square] = code;
+            code += 1;
+        }
+    }
+
+    //the a1-d1-d4 triangle, with the squares on the diagonal numbered last.
+    let mut diagonal = Vec::new();
+    code = 0;
+    for square in 0..28 {
+        if off_diagonal(square) < 0 && file_of(square) <= 3 {
+            maps.map_a1d1d4[square] = code;
+            code += 1;
+        } else if off_diagonal(square) == 0 && file_of(square) <= 3 {
+            diagonal.push(square);
+        }
+    }
+    for square in diagonal {
+        maps.map_a1d1d4[square] = code;
+        code += 1;
+    }
+
+    //all 462 ways to place two kings apart with the first in the triangle. When the first is on the diagonal the
+    //second can't be above it, and positions with both on the diagonal come last.
+    let mut both_on_diagonal = Vec::new();
+    code = 0;
+    for triangle_index in 0..10 {
+        for first in 0..28 {
+            //b1 is the only square of the triangle numbered 0. Squares outside the triangle are 0 too.
+            if maps.map_a1d1d4[first] != triangle_index || (triangle_index == 0 && first != 1) {
+                continue;
+            }
+            for second in 0..64 {
+                if (king_attacks(first) | square_bit(first)) & square_bit(second) != 0 {
+                    continue;
+                }
+                if off_diagonal(first) == 0 && off_diagonal(second) > 0 {
+                    continue;
+                }
+                if off_diagonal(first) == 0 && off_diagonal(second) == 0 {
+                    both_on_diagonal.push((triangle_index, second));
+                } else {
+                    maps.map_kk[triangle_index][second] = code;
+                    code += 1;
+                }
+            }
+        }
+    }
+    for (triangle_index, second) in both_on_diagonal {
+        maps.map_kk[triangle_index][second] = code;
+        code += 1;
+    }
+
+    //binomial[k][n] is the number of ways to choose k squares out of n.
+    maps.binomial[0][0] = 1;
+    for n in 1..64 {
+        for k in 0..6.min(n + 1) {
+            maps.binomial[k][n] = if k > 0 {
+                maps.binomial[k - 1][n - 1]
+            } else {
+                0
+            } + if k < n { maps.binomial[k][n - 1] } else { 0 };
+        }
+    }
+
+    //pawns go on a2 to h7. The pawn nearest the edge, and lowest on its file, leads, and map_pawns counts the
+    //squares the others still have left when it stands on a given square.
+    let mut available_squares = 47;
+    for lead_pawns in 1..=5 {
+        for file in 0..4 {
+            let mut index = 0;
+            for rank in 1..7 {
+                let square = rank * 8 + file;
+                if lead_pawns == 1 {
+                    maps.map_pawns[square] = available_squares;
+                    available_squares -= 1;
+                    maps.map_pawns[flip_file(square)] = available_squares;
+                    available_squares = available_squares.saturating_sub(1);
+                }
+                maps.lead_pawn_index[lead_pawns][square] = index;
+                index += maps.binomial[lead_pawns - 1][maps.map_pawns[square]];
+            }
+            maps.lead_pawns_size[lead_pawns][file] = index;
+        }
+    }
+
+    maps
+}
+
+//each piece is written into a table as its kind, 1 for a pawn up to 6 for a king, plus 8 when it's black.
+fn table_piece_code(piece: Piece) -> u8 {
+    let color_code = match piece.color {
+        PieceColor::White => 0,
+        PieceColor::Black => 8,
+    };
+    piece.kind.index() as u8 + 1 + color_code
+}
+
+//table names list each side's pieces from king down to pawn, like KRvKN.
+fn side_material(board: &Board, color: PieceColor) -> String {
+    let mut material = String::new();
+    for (piece_kind, letter) in [
+        (PieceKind::King, 'K'),
+        (PieceKind::Queen, 'Q'),
+        (PieceKind::Rook, 'R'),
+        (PieceKind::Bishop, 'B'),
+        (PieceKind::Knight, 'N'),
+        (PieceKind::Pawn, 'P'),
+    ] {
+        let count = board
+            .piece_bitboard(Piece {
+                color,
+                kind: piece_kind,
+            })
+            .count_ones();
+        for _ in 0..count {
+            material.push(letter);
+        }
+    }
+    material
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TableKind {
+    Wdl,
+    Dtz,
+}
+
+//what a table's name tells us about the positions in it.
+#[derive(Clone, Debug)]
+struct TableMaterial {
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique_pieces: bool,
+    //the leading side's pawns, then the other side's. The side with fewer pawns leads.
+    pawn_count: [usize; 2],
+    //both sides have the same pieces, like KRvKR.
+    symmetric: bool,
+    //every piece as it's written into the table, lowest code first.
+    piece_codes: Vec<u8>,
+}
+
+impl TableMaterial {
+    fn from_name(name: &str) -> Result<TableMaterial, &'static str> {
+        let (white_side, black_side) = match name.split_once('v') {
+            Some(sides) => sides,
+            None => return Err("a tablebase name has to look like KRvK"),
+        };
+        let mut counts = [[0; 6]; 2];
+        let mut piece_codes = Vec::new();
+        for (side_index, side) in [white_side, black_side].iter().enumerate() {
+            if !side.starts_with('K') || side.matches('K').count() != 1 {
+                return Err("each side of a tablebase name needs exactly one king");
+            }
+            for letter in side.chars() {
+                let piece_kind = match letter {
+                    'K' => PieceKind::King,
+                    'Q' => PieceKind::Queen,
+                    'R' => PieceKind::Rook,
+                    'B' => PieceKind::Bishop,
+                    'N' => PieceKind::Knight,
+                    'P' => PieceKind::Pawn,
+                    _ => return Err("a tablebase name can only use the letters KQRBNP"),
+                };
+                counts[side_index][piece_kind.index()] += 1;
+                piece_codes.push(table_piece_code(Piece {
+                    color: if side_index == 0 {
+                        PieceColor::White
+                    } else {
+                        PieceColor::Black
+                    },
+                    kind: piece_kind,
+                }));
+            }
+        }
+
+        let piece_count = white_side.len() + black_side.len();
+        if piece_count > MAX_TABLEBASE_PIECES {
+            return Err("tablebases only go up to seven pieces");
+        }
+        let pawn_index = PieceKind::Pawn.index();
+        let (white_pawns, black_pawns) = (counts[0][pawn_index], counts[1][pawn_index]);
+        let white_leads = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+        let has_unique_pieces = counts.iter().any(|side_counts| {
+            PieceKind::iterator()
+                .filter(|piece_kind| **piece_kind != PieceKind::King)
+                .any(|piece_kind| side_counts[piece_kind.index()] == 1)
+        });
+
+        Ok(TableMaterial {
+            piece_count,
+            has_pawns: white_pawns + black_pawns > 0,
+            has_unique_pieces,
+            pawn_count: if white_leads {
+                [white_pawns, black_pawns]
+            } else {
+                [black_pawns, white_pawns]
+            },
+            symmetric: white_side == black_side,
+            piece_codes: {
+                piece_codes.sort();
+                piece_codes
+            },
+        })
+    }
+}
+
+//everything needed to pull values back out of one compressed run of positions. A table has one of these per side
+//to move it stores, and per file of the leading pawn when there are pawns.
+#[derive(Clone, Debug, Default)]
+struct PairsData {
+    flags: u8,
+    min_symbol_length: u8,
+    block_size: u64,
+    span: u64,
+    block_count: usize,
+    block_length_size: usize,
+    sparse_index_size: usize,
+    //where each part starts in the file.
+    lowest_symbol: usize,
+    symbol_tree: usize,
+    sparse_index: usize,
+    block_lengths: usize,
+    data: usize,
+    base64: Vec<u64>,
+    //how many values, less one, each symbol stands for.
+    symbol_lengths: Vec<u8>,
+    pieces: [u8; MAX_TABLEBASE_PIECES],
+    group_index: [u64; MAX_TABLEBASE_PIECES + 1],
+    group_length: [usize; MAX_TABLEBASE_PIECES + 1],
+    //where the win, loss, cursed win and blessed loss value maps start, for mapped DTZ tables.
+    map_index: [usize; 4],
+}
+
+impl PairsData {
+    fn table_size(&self) -> u64 {
+        let group_count = self
+            .group_length
+            .iter()
+            .position(|group_length| *group_length == 0)
+            .unwrap_or(MAX_TABLEBASE_PIECES);
+        self.group_index[group_count]
+    }
+}
+
+//reads the header of a table front to back, refusing to go past the end of the file.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ByteReader<'_> {
+    fn read_u8(&mut self) -> Result<u8, &'static str> {
+        let byte = *self.bytes.get(self.position).ok_or(CORRUPT_TABLE)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, &'static str> {
+        let value = read_u16_le(self.bytes, self.position)?;
+        self.position += 2;
+        Ok(value)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, &'static str> {
+        let low = self.read_u16()? as u32;
+        let high = self.read_u16()? as u32;
+        Ok(low | (high << 16))
+    }
+
+    fn skip(&mut self, count: usize) {
+        self.position += count;
+    }
+
+    fn align(&mut self, alignment: usize) {
+        self.position = self.position.div_ceil(alignment) * alignment;
+    }
+}
+
+fn read_u16_le(bytes: &[u8], position: usize) -> Result<u16, &'static str> {
+    match (bytes.get(position), bytes.get(position + 1)) {
+        (Some(low), Some(high)) => Ok(u16::from_le_bytes([*low, *high])),
+        _ => Err(CORRUPT_TABLE),
+    }
+}
+
+fn read_u32_le(bytes: &[u8], position: usize) -> Result<u32, &'static str> {
+    let low = read_u16_le(bytes, position)? as u32;
+    let high = read_u16_le(bytes, position + 2)? as u32;
+    Ok(low | (high << 16))
+}
+
+//the compressed data is read a word at a time, and the last word of a block can run a little past the end of the
+//file. Anything out there counts as zeros.
+fn read_u32_be_padded(bytes: &[u8], position: usize) -> u32 {
+    let mut word = [0; 4];
+    for (offset, byte) in word.iter_mut().enumerate() {
+        *byte = bytes.get(position + offset).copied().unwrap_or(0);
+    }
+    u32::from_be_bytes(word)
+}
+
+//the symbol tree stores two 12 bit symbols in three bytes, the pair a symbol expands into.
+fn symbol_pair(
+    bytes: &[u8],
+    pairs: &PairsData,
+    symbol: usize,
+) -> Result<(usize, usize), &'static str> {
+    let start = pairs.symbol_tree + symbol * 3;
+    match bytes.get(start..start + 3) {
+        Some(pair_bytes) => {
+            let left = (((pair_bytes[1] & 0xF) as usize) << 8) | pair_bytes[0] as usize;
+            let right = ((pair_bytes[2] as usize) << 4) | (pair_bytes[1] >> 4) as usize;
+            Ok((left, right))
+        }
+        None => Err(CORRUPT_TABLE),
+    }
+}
+
+fn set_symbol_length(
+    bytes: &[u8],
+    pairs: &mut PairsData,
+    symbol: usize,
+    visited: &mut [bool],
+) -> Result<u8, &'static str> {
+    visited[symbol] = true;
+    let (left, right) = symbol_pair(bytes, pairs, symbol)?;
+    //a symbol with no right half is a plain value.
+    if right == 0xFFF {
+        return Ok(0);
+    }
+    if left >= visited.len() || right >= visited.len() {
+        return Err(CORRUPT_TABLE);
+    }
+    for half in [left, right] {
+        if !visited[half] {
+            pairs.symbol_lengths[half] = set_symbol_length(bytes, pairs, half, visited)?;
+        }
+    }
+    Ok(pairs.symbol_lengths[left]
+        .wrapping_add(pairs.symbol_lengths[right])
+        .wrapping_add(1))
+}
+
+fn read_pairs_data(reader: &mut ByteReader, pairs: &mut PairsData) -> Result<(), &'static str> {
+    pairs.flags = reader.read_u8()?;
+    if pairs.flags & SINGLE_VALUE_FLAG != 0 {
+        //every position holds the same value, so that's all there is.
+        pairs.min_symbol_length = reader.read_u8()?;
+        return Ok(());
+    }
+
+    let block_size_bits = reader.read_u8()?;
+    let span_bits = reader.read_u8()?;
+    if block_size_bits >= 32 || span_bits >= 64 {
+        return Err(CORRUPT_TABLE);
+    }
+    pairs.block_size = 1 << block_size_bits;
+    pairs.span = 1 << span_bits;
+    pairs.sparse_index_size = pairs.table_size().div_ceil(pairs.span) as usize;
+    let padding = reader.read_u8()? as usize;
+    pairs.block_count = reader.read_u32()? as usize;
+    pairs.block_length_size = pairs.block_count + padding;
+
+    let max_symbol_length = reader.read_u8()?;
+    pairs.min_symbol_length = reader.read_u8()?;
+    if max_symbol_length < pairs.min_symbol_length || max_symbol_length > 64 {
+        return Err(CORRUPT_TABLE);
+    }
+    pairs.lowest_symbol = reader.position;
+    let length_count = (max_symbol_length - pairs.min_symbol_length) as usize + 1;
+    reader.skip(length_count * 2);
+
+    //a canonical Huffman code. Longer codes have lower values, so base64[l] is the lowest code of each length
+    //padded out to 64 bits, and a code's length is the first l whose base it isn't below.
+    pairs.base64 = vec![0; length_count];
+    for length in (0..length_count - 1).rev() {
+        let lowest = read_u16_le(reader.bytes, pairs.lowest_symbol + length * 2)? as u64;
+        let next_lowest = read_u16_le(reader.bytes, pairs.lowest_symbol + (length + 1) * 2)? as u64;
+        pairs.base64[length] = pairs.base64[length + 1]
+            .wrapping_add(lowest)
+            .wrapping_sub(next_lowest)
+            / 2;
+    }
+    for (length, base) in pairs.base64.iter_mut().enumerate() {
+        let shift = 64 - length as u32 - pairs.min_symbol_length as u32;
+        *base = base.checked_shl(shift).unwrap_or(0);
+    }
+
+    let symbol_count = reader.read_u16()? as usize;
+    pairs.symbol_tree = reader.position;
+    reader.skip(symbol_count * 3 + (symbol_count & 1));
+
+    //symbols are built up by pairing other symbols, so work out how many values each one expands to.
+    pairs.symbol_lengths = vec![0; symbol_count];
+    let mut visited = vec![false; symbol_count];
+    for symbol in 0..symbol_count {
+        if !visited[symbol] {
+            pairs.symbol_lengths[symbol] =
+                set_symbol_length(reader.bytes, pairs, symbol, &mut visited)?;
+        }
+    }
+    Ok(())
+}
+
+//splits a table's pieces into the groups they're numbered in, and works out how much each group multiplies the
+//position number by.
+fn set_groups(
+    material: &TableMaterial,
+    pairs: &mut PairsData,
+    order: [usize; 2],
+    file: usize,
+) -> Result<(), &'static str> {
+    let maps = index_maps();
+    let mut group_count = 0;
+    let mut first_length: isize = if material.has_pawns {
+        0
+    } else if material.has_unique_pieces {
+        3
+    } else {
+        2
+    };
+    pairs.group_length[0] = 1;
+    for piece_index in 1..material.piece_count {
+        first_length -= 1;
+        if first_length > 0 || pairs.pieces[piece_index] == pairs.pieces[piece_index - 1] {
+            pairs.group_length[group_count] += 1;
+        } else {
+            group_count += 1;
+            pairs.group_length[group_count] = 1;
+        }
+    }
+    group_count += 1;
+    pairs.group_length[group_count] = 0;
+
+    let pawns_on_both_sides = material.has_pawns && material.pawn_count[1] > 0;
+    let mut next = if pawns_on_both_sides { 2 } else { 1 };
+    let mut free_squares = 64
+        - pairs.group_length[0]
+        - if pawns_on_both_sides {
+            pairs.group_length[1]
+        } else {
+            0
+        };
+    let mut index: u64 = 1;
+    let mut order_index = 0;
+    while next < group_count || order_index == order[0] || order_index == order[1] {
+        if order_index == order[0] {
+            //the leading pawns or pieces.
+            pairs.group_index[0] = index;
+            index *= if material.has_pawns {
+                maps.lead_pawns_size[pairs.group_length[0]][file]
+            } else if material.has_unique_pieces {
+                31332
+            } else {
+                462
+            };
+        } else if order_index == order[1] {
+            //the other side's pawns.
+            pairs.group_index[1] = index;
+            index *= maps.binomial[pairs.group_length[1]][48 - pairs.group_length[0]];
+        } else {
+            if next >= group_count {
+                return Err(CORRUPT_TABLE);
+            }
+            pairs.group_index[next] = index;
+            index *= maps.binomial[pairs.group_length[next]][free_squares];
+            free_squares -= pairs.group_length[next];
+            next += 1;
+        }
+        order_index += 1;
+    }
+    pairs.group_index[group_count] = index;
+    Ok(())
+}
+
+struct Table {
+    kind: TableKind,
+    bytes: Vec<u8>,
+    material: TableMaterial,
+    sides: usize,
+    //indexed by side to move, then by the leading pawn's file.
+    pairs: Vec<Vec<PairsData>>,
+    dtz_map: usize,
+}
+
+impl Table {
+    //file_name is how the tables are named on disk, like KRvK.rtbw.
+    fn from_bytes(file_name: &str, bytes: Vec<u8>) -> Result<Table, &'static str> {
+        let (name, kind, magic) = if let Some(name) = file_name.strip_suffix(WDL_SUFFIX) {
+            (name, TableKind::Wdl, WDL_MAGIC)
+        } else if let Some(name) = file_name.strip_suffix(DTZ_SUFFIX) {
+            (name, TableKind::Dtz, DTZ_MAGIC)
+        } else {
+            return Err("tablebase files end in .rtbw or .rtbz");
+        };
+        let material = TableMaterial::from_name(name)?;
+        if bytes.get(0..4) != Some(&magic[..]) {
+            return Err(CORRUPT_TABLE);
+        }
+
+        let mut reader = ByteReader {
+            bytes: &bytes,
+            position: 4,
+        };
+        let flags = reader.read_u8()?;
+        if (flags & HAS_PAWNS_FLAG != 0) != material.has_pawns
+            || (flags & SPLIT_FLAG != 0) == material.symmetric
+        {
+            return Err(CORRUPT_TABLE);
+        }
+
+        //WDL tables store both sides to move, unless the material is the same on both sides. DTZ tables store one.
+        let sides = if kind == TableKind::Wdl && !material.symmetric {
+            2
+        } else {
+            1
+        };
+        let files = if material.has_pawns { 4 } else { 1 };
+        let pawns_on_both_sides = material.has_pawns && material.pawn_count[1] > 0;
+        let mut pairs = vec![vec![PairsData::default(); files]; sides];
+
+        for file in 0..files {
+            let order_byte = reader.read_u8()?;
+            let pawn_order_byte = if pawns_on_both_sides {
+                reader.read_u8()?
+            } else {
+                0xFF
+            };
+            let orders = [
+                [
+                    (order_byte & 0xF) as usize,
+                    (pawn_order_byte & 0xF) as usize,
+                ],
+                [(order_byte >> 4) as usize, (pawn_order_byte >> 4) as usize],
+            ];
+            for piece_index in 0..material.piece_count {
+                let piece_byte = reader.read_u8()?;
+                for (side, side_pairs) in pairs.iter_mut().enumerate() {
+                    side_pairs[file].pieces[piece_index] = if side == 1 {
+                        piece_byte >> 4
+                    } else {
+                        piece_byte & 0xF
+                    };
+                }
+            }
+            for (side, side_pairs) in pairs.iter_mut().enumerate() {
+                //the pieces have to be the ones the name promises, or none of the numbering below makes sense.
+                let mut file_pieces = side_pairs[file].pieces[..material.piece_count].to_vec();
+                file_pieces.sort();
+                if file_pieces != material.piece_codes {
+                    return Err(CORRUPT_TABLE);
+                }
+                set_groups(&material, &mut side_pairs[file], orders[side], file)?;
+            }
+        }
+        reader.align(2);
+
+        for file in 0..files {
+            for side_pairs in pairs.iter_mut() {
+                read_pairs_data(&mut reader, &mut side_pairs[file])?;
+            }
+        }
+
+        //DTZ tables can squeeze their values through a map, one for each kind of result.
+        let dtz_map = reader.position;
+        if kind == TableKind::Dtz {
+            for file_pairs in pairs[0].iter_mut() {
+                if file_pairs.flags & MAPPED_FLAG == 0 {
+                    continue;
+                }
+                if file_pairs.flags & WIDE_FLAG != 0 {
+                    reader.align(2);
+                    for map_index in file_pairs.map_index.iter_mut() {
+                        *map_index = (reader.position - dtz_map) / 2 + 1;
+                        let map_length = reader.read_u16()? as usize;
+                        reader.skip(map_length * 2);
+                    }
+                } else {
+                    for map_index in file_pairs.map_index.iter_mut() {
+                        *map_index = reader.position - dtz_map + 1;
+                        let map_length = reader.read_u8()? as usize;
+                        reader.skip(map_length);
+                    }
+                }
+            }
+            reader.align(2);
+        }
+
+        for file in 0..files {
+            for side_pairs in pairs.iter_mut() {
+                side_pairs[file].sparse_index = reader.position;
+                reader.skip(side_pairs[file].sparse_index_size * 6);
+            }
+        }
+        for file in 0..files {
+            for side_pairs in pairs.iter_mut() {
+                side_pairs[file].block_lengths = reader.position;
+                reader.skip(side_pairs[file].block_length_size * 2);
+            }
+        }
+        for file in 0..files {
+            for side_pairs in pairs.iter_mut() {
+                reader.align(64);
+                side_pairs[file].data = reader.position;
+                reader.skip(side_pairs[file].block_count * side_pairs[file].block_size as usize);
+            }
+        }
+        if reader.position > bytes.len() {
+            return Err(CORRUPT_TABLE);
+        }
+
+        Ok(Table {
+            kind,
+            bytes,
+            material,
+            sides,
+            pairs,
+            dtz_map,
+        })
+    }
+
+    fn pairs_for(&self, side: usize, file: usize) -> &PairsData {
+        &self.pairs[side % self.sides][if self.material.has_pawns { file } else { 0 }]
+    }
+
+    //turns the pieces into the number of their position in the table. Pieces come as (square, piece) from a1 up,
+    //colored as they are in the table, so white is always the side listed first in its name. Hands back the side to
+    //move and the leading pawn's file along with the number, since those pick which part of the table to read.
+    fn position_index(
+        &self,
+        placed_pieces: &[(usize, Piece)],
+        black_to_move: bool,
+    ) -> (usize, usize, u64) {
+        let maps = index_maps();
+        let mut squares = Vec::with_capacity(placed_pieces.len());
+        let mut pieces = Vec::with_capacity(placed_pieces.len());
+        let mut lead_pawn_count = 0;
+        let mut table_file = 0;
+        let side_to_move = black_to_move as usize;
+
+        if self.material.has_pawns {
+            //the leading pawns come first, and their color is the one the table's first piece has.
+            let lead_pawn_code = self.pairs_for(0, 0).pieces[0];
+            for (square, piece) in placed_pieces {
+                if table_piece_code(*piece) == lead_pawn_code {
+                    squares.push(*square);
+                    pieces.push(lead_pawn_code);
+                }
+            }
+            lead_pawn_count = squares.len();
+            let leading = (0..lead_pawn_count)
+                .max_by_key(|pawn_index| maps.map_pawns[squares[*pawn_index]])
+                .unwrap_or(0);
+            squares.swap(0, leading);
+            table_file = file_of(squares[0]).min(7 - file_of(squares[0]));
+        }
+        for (square, piece) in placed_pieces {
+            let piece_code = table_piece_code(*piece);
+            if !(self.material.has_pawns && piece_code == pieces.first().copied().unwrap_or(0)) {
+                squares.push(*square);
+                pieces.push(piece_code);
+            }
+        }
+
+        //put the pieces in the same order the table lists them.
+        let pairs = self.pairs_for(side_to_move, table_file);
+        let size = squares.len();
+        for piece_index in lead_pawn_count..size.saturating_sub(1) {
+            for other_index in piece_index + 1..size {
+                if pairs.pieces[piece_index] == pieces[other_index] {
+                    pieces.swap(piece_index, other_index);
+                    squares.swap(piece_index, other_index);
+                    break;
+                }
+            }
+        }
+
+        //mirror so the leading piece is on the queenside.
+        if file_of(squares[0]) > 3 {
+            for square in squares.iter_mut() {
+                *square = flip_file(*square);
+            }
+        }
+
+        let mut index: u64;
+        if self.material.has_pawns {
+            index = maps.lead_pawn_index[lead_pawn_count][squares[0]];
+            squares[1..lead_pawn_count].sort_by_key(|square| maps.map_pawns[*square]);
+            for (pawn_index, square) in squares.iter().enumerate().take(lead_pawn_count).skip(1) {
+                index += maps.binomial[pawn_index][maps.map_pawns[*square]];
+            }
+        } else {
+            //without pawns the board can be mirrored top to bottom and across the diagonal too, which puts the
+            //leading piece in the a1-d1-d4 triangle.
+            if rank_of(squares[0]) > 3 {
+                for square in squares.iter_mut() {
+                    *square = flip_rank(*square);
+                }
+            }
+            for piece_index in 0..pairs.group_length[0] {
+                let off = off_diagonal(squares[piece_index]);
+                if off == 0 {
+                    continue;
+                }
+                if off > 0 {
+                    for square in squares[piece_index..].iter_mut() {
+                        *square = ((*square >> 3) | (*square << 3)) & 63;
+                    }
+                }
+                break;
+            }
+
+            if self.material.has_unique_pieces {
+                let adjust1 = (squares[1] > squares[0]) as usize;
+                let adjust2 =
+                    (squares[2] > squares[0]) as usize + (squares[2] > squares[1]) as usize;
+                index = if off_diagonal(squares[0]) != 0 {
+                    ((maps.map_a1d1d4[squares[0]] * 63 + squares[1] - adjust1) * 62 + squares[2]
+                        - adjust2) as u64
+                } else if off_diagonal(squares[1]) != 0 {
+                    ((6 * 63 + rank_of(squares[0]) * 28 + maps.map_b1h1h7[squares[1]]) * 62
+                        + squares[2]
+                        - adjust2) as u64
+                } else if off_diagonal(squares[2]) != 0 {
+                    (6 * 63 * 62
+                        + 4 * 28 * 62
+                        + rank_of(squares[0]) * 7 * 28
+                        + (rank_of(squares[1]) - adjust1) * 28
+                        + maps.map_b1h1h7[squares[2]]) as u64
+                } else {
+                    (6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + rank_of(squares[0]) * 6 * 7
+                        + (rank_of(squares[1]) - adjust1) * 6
+                        + (rank_of(squares[2]) - adjust2)) as u64
+                };
+            } else {
+                index = maps.map_kk[maps.map_a1d1d4[squares[0]]][squares[1]] as u64;
+            }
+        }
+
+        //the rest of the groups, each numbered by which squares it takes out of the ones left over.
+        index *= pairs.group_index[0];
+        let mut group_start = pairs.group_length[0];
+        let mut remaining_pawns = self.material.has_pawns && self.material.pawn_count[1] > 0;
+        let mut next = 1;
+        while pairs.group_length[next] != 0 {
+            let group_end = group_start + pairs.group_length[next];
+            squares[group_start..group_end].sort();
+            let mut group_number = 0;
+            for (piece_index, square) in squares[group_start..group_end].iter().enumerate() {
+                let adjust = squares[..group_start]
+                    .iter()
+                    .filter(|earlier| *square > **earlier)
+                    .count();
+                let pawn_adjust = if remaining_pawns { 8 } else { 0 };
+                group_number +=
+                    maps.binomial[piece_index + 1][square.saturating_sub(adjust + pawn_adjust)];
+            }
+            remaining_pawns = false;
+            index += group_number * pairs.group_index[next];
+            group_start = group_end;
+            next += 1;
+        }
+
+        (side_to_move, table_file, index)
+    }
+
+    //reads one value back out of the compressed blocks.
+    fn decompress(&self, pairs: &PairsData, index: u64) -> Result<usize, &'static str> {
+        if pairs.flags & SINGLE_VALUE_FLAG != 0 {
+            return Ok(pairs.min_symbol_length as usize);
+        }
+        let bytes = &self.bytes;
+
+        //the sparse index points into the block lengths every span positions, halfway through each span. From
+        //there we walk block by block to the one holding our position.
+        let sparse_entry = (index / pairs.span) as usize;
+        if sparse_entry >= pairs.sparse_index_size {
+            return Err(CORRUPT_TABLE);
+        }
+        let entry_start = pairs.sparse_index + sparse_entry * 6;
+        let mut block = read_u32_le(bytes, entry_start)? as usize;
+        let mut offset = read_u16_le(bytes, entry_start + 4)? as i64;
+        offset += (index % pairs.span) as i64 - (pairs.span / 2) as i64;
+
+        let block_length = |block: usize| -> Result<i64, &'static str> {
+            if block >= pairs.block_length_size {
+                return Err(CORRUPT_TABLE);
+            }
+            Ok(read_u16_le(bytes, pairs.block_lengths + block * 2)? as i64)
+        };
+        while offset < 0 {
+            if block == 0 {
+                return Err(CORRUPT_TABLE);
+            }
+            block -= 1;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        //each block is a run of Huffman codes, each standing for one or more values.
+        let mut position = pairs.data + block * pairs.block_size as usize;
+        let mut buffer = ((read_u32_be_padded(bytes, position) as u64) << 32)
+            | read_u32_be_padded(bytes, position + 4) as u64;
+        position += 8;
+        let mut buffer_bits = 64;
+        let mut symbol;
+        loop {
+            let mut length = 0;
+            while length + 1 < pairs.base64.len() && buffer < pairs.base64[length] {
+                length += 1;
+            }
+            let shift = 64 - length as u32 - pairs.min_symbol_length as u32;
+            symbol = (buffer.wrapping_sub(pairs.base64[length]))
+                .checked_shr(shift)
+                .unwrap_or(0) as usize;
+            symbol += read_u16_le(bytes, pairs.lowest_symbol + length * 2)? as usize;
+            let symbol_length = *pairs.symbol_lengths.get(symbol).ok_or(CORRUPT_TABLE)? as i64;
+            if offset < symbol_length + 1 {
+                break;
+            }
+            offset -= symbol_length + 1;
+            let code_length = length as u32 + pairs.min_symbol_length as u32;
+            buffer = buffer.checked_shl(code_length).unwrap_or(0);
+            buffer_bits -= code_length as i32;
+            if buffer_bits <= 32 {
+                buffer_bits += 32;
+                buffer |= (read_u32_be_padded(bytes, position) as u64) << (64 - buffer_bits);
+                position += 4;
+            }
+        }
+
+        //a symbol made of a pair holds its left half's values first, then its right half's.
+        while pairs.symbol_lengths[symbol] != 0 {
+            let (left, right) = symbol_pair(bytes, pairs, symbol)?;
+            let left_length = *pairs.symbol_lengths.get(left).ok_or(CORRUPT_TABLE)? as i64;
+            if offset < left_length + 1 {
+                symbol = left;
+            } else {
+                offset -= left_length + 1;
+                symbol = right;
+            }
+            if symbol >= pairs.symbol_lengths.len() {
+                return Err(CORRUPT_TABLE);
+            }
+        }
+        Ok(symbol_pair(bytes, pairs, symbol)?.0)
+    }
+
+    //DTZ tables store values as moves unless told otherwise, and sometimes squeezed through a map.
+    fn dtz_value(&self, file: usize, value: usize, wdl: Wdl) -> Result<i32, &'static str> {
+        let pairs = self.pairs_for(0, file);
+        let mut value = value;
+        if pairs.flags & MAPPED_FLAG != 0 {
+            let map_index = match wdl {
+                Wdl::Win | Wdl::Draw => pairs.map_index[0],
+                Wdl::Loss => pairs.map_index[1],
+                Wdl::CursedWin => pairs.map_index[2],
+                Wdl::BlessedLoss => pairs.map_index[3],
+            };
+            value = if pairs.flags & WIDE_FLAG != 0 {
+                read_u16_le(&self.bytes, self.dtz_map + (map_index + value) * 2)? as usize
+            } else {
+                *self
+                    .bytes
+                    .get(self.dtz_map + map_index + value)
+                    .ok_or(CORRUPT_TABLE)? as usize
+            };
+        }
+        let mut dtz = value as i32;
+        if (wdl == Wdl::Win && pairs.flags & WIN_PLIES_FLAG == 0)
+            || (wdl == Wdl::Loss && pairs.flags & LOSS_PLIES_FLAG == 0)
+            || wdl == Wdl::CursedWin
+            || wdl == Wdl::BlessedLoss
+        {
+            dtz *= 2;
+        }
+        Ok(dtz + 1)
+    }
+}
+
+//what reading one table gave back.
+enum TableProbe {
+    Value(i32),
+    //a DTZ table that only stores the other side to move.
+    OtherSideToMove,
+}
+
+fn is_capture(board: &Board, chess_move: &Move) -> bool {
+    match board.retreive_square(&chess_move.to) {
+        Ok(Square::Full(_)) => true,
+        //en passant is the only way a pawn moves sideways onto an empty square.
+        _ => is_pawn_move(board, chess_move) && chess_move.from.letter != chess_move.to.letter,
+    }
+}
+
+fn is_pawn_move(board: &Board, chess_move: &Move) -> bool {
+    matches!(
+        board.retreive_square(&chess_move.from),
+        Ok(Square::Full(Piece {
+            kind: PieceKind::Pawn,
+            ..
+        }))
+    )
+}
+
+fn is_checkmate(board: &Board) -> bool {
+    board.is_king_in_danger(board.get_turn()) && board.legal_moves().is_empty()
+}
+
+//a directory of Syzygy files. Tables are only read in when a probe first needs them.
+#[derive(Default)]
+pub struct Tablebase {
+    opt_directory: Option<PathBuf>,
+    //the file names we know about, like KRvK.rtbw.
+    file_names: HashSet<String>,
+    tables: RefCell<HashMap<String, Rc<Table>>>,
+}
+
+impl Tablebase {
+    pub fn open(directory: &str) -> io::Result<Tablebase> {
+        let mut tablebase = Tablebase {
+            opt_directory: Some(PathBuf::from(directory)),
+            ..Tablebase::default()
+        };
+        for entry in fs::read_dir(directory)? {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(WDL_SUFFIX) || file_name.ends_with(DTZ_SUFFIX) {
+                tablebase.file_names.insert(file_name);
+            }
+        }
+        Ok(tablebase)
+    }
+
+    //adds a table that's already in memory, named the way it would be on disk.
+    pub fn add_table(&mut self, file_name: &str, bytes: Vec<u8>) -> Result<(), &'static str> {
+        let table = Table::from_bytes(file_name, bytes)?;
+        self.file_names.insert(file_name.to_string());
+        self.tables
+            .borrow_mut()
+            .insert(file_name.to_string(), Rc::new(table));
+        Ok(())
+    }
+
+    //the most pieces, kings included, of any table we have.
+    pub fn max_pieces(&self) -> usize {
+        self.file_names
+            .iter()
+            .map(|file_name| {
+                file_name
+                    .chars()
+                    .filter(|letter| "KQRBNP".contains(*letter))
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn load_table(&self, file_name: &str) -> Result<Rc<Table>, &'static str> {
+        if let Some(table) = self.tables.borrow().get(file_name) {
+            return Ok(Rc::clone(table));
+        }
+        let directory = self
+            .opt_directory
+            .as_ref()
+            .ok_or("there's no tablebase file for this material")?;
+        let bytes =
+            fs::read(directory.join(file_name)).map_err(|_| "a tablebase file couldn't be read")?;
+        let table = Rc::new(Table::from_bytes(file_name, bytes)?);
+        self.tables
+            .borrow_mut()
+            .insert(file_name.to_string(), Rc::clone(&table));
+        Ok(table)
+    }
+
+    fn probe_table(
+        &self,
+        board: &Board,
+        kind: TableKind,
+        wdl: Wdl,
+    ) -> Result<TableProbe, &'static str> {
+        if board.occupied_bitboard().count_ones() == 2 {
+            //two bare kings are a draw, and there's no file for them.
+            return Ok(TableProbe::Value(0));
+        }
+
+        //tables are named with the stronger side first. If black is the stronger side here, we look the position up
+        //with the colors swapped and the board turned around.
+        let white_material = side_material(board, PieceColor::White);
+        let black_material = side_material(board, PieceColor::Black);
+        let suffix = match kind {
+            TableKind::Wdl => WDL_SUFFIX,
+            TableKind::Dtz => DTZ_SUFFIX,
+        };
+        let straight_name = format!("{}v{}{}", white_material, black_material, suffix);
+        let swapped_name = format!("{}v{}{}", black_material, white_material, suffix);
+        let (file_name, black_stronger) = if self.file_names.contains(&straight_name) {
+            (straight_name, false)
+        } else if self.file_names.contains(&swapped_name) {
+            (swapped_name, true)
+        } else {
+            return Err("there's no tablebase file for this material");
+        };
+        let table = self.load_table(&file_name)?;
+
+        //when both sides have the same pieces the table only stores white to move, so black to move gets turned
+        //around too.
+        let black_to_move = board.get_turn() == PieceColor::Black;
+        let flip = black_stronger || (table.material.symmetric && black_to_move);
+        let mut placed_pieces = Vec::new();
+        for square in square_indices(board.occupied_bitboard()) {
+            if let Square::Full(piece) = board.retreive_square(&coordinates_from_index(square))? {
+                placed_pieces.push(if flip {
+                    (
+                        flip_rank(square),
+                        Piece {
+                            color: piece.color.get_inverse_color(),
+                            kind: piece.kind,
+                        },
+                    )
+                } else {
+                    (square, piece)
+                });
+            }
+        }
+
+        let (side_to_move, table_file, index) =
+            table.position_index(&placed_pieces, flip != black_to_move);
+        let pairs = table.pairs_for(side_to_move, table_file);
+        if table.kind == TableKind::Dtz {
+            let stored_side = (pairs.flags & STM_FLAG) as usize;
+            //a symmetric table without pawns has been turned around to white to move already.
+            let stores_both_sides = table.material.symmetric && !table.material.has_pawns;
+            if stored_side != side_to_move && !stores_both_sides {
+                return Ok(TableProbe::OtherSideToMove);
+            }
+        }
+
+        let value = table.decompress(pairs, index)?;
+        match table.kind {
+            TableKind::Wdl => Ok(TableProbe::Value(value as i32 - 2)),
+            TableKind::Dtz => Ok(TableProbe::Value(table.dtz_value(table_file, value, wdl)?)),
+        }
+    }
+
+    //the tables don't bother storing the right value when a capture, or a pawn move for DTZ, already wins, and they
+    //don't know about en passant at all. So we try those moves ourselves and keep the best of them and the stored
+    //value. The bool says whether one of those moves is the best there is.
+    fn search_wdl(
+        &self,
+        board: &mut Board,
+        check_zeroing_moves: bool,
+    ) -> Result<(Wdl, bool), &'static str> {
+        let legal_moves = board.legal_moves();
+        let mut best_value = Wdl::Loss;
+        let mut searched_moves = 0;
+        for legal_move in legal_moves.iter() {
+            let zeroing = is_capture(board, legal_move)
+                || (check_zeroing_moves && is_pawn_move(board, legal_move));
+            if !zeroing {
+                continue;
+            }
+            searched_moves += 1;
+            let undo = board.make_move(legal_move);
+            let opt_reply = self.search_wdl(board, false);
+            board.unmake_move(undo);
+            let value = opt_reply?.0.opposite();
+            if value > best_value {
+                best_value = value;
+                if value == Wdl::Win {
+                    return Ok((value, true));
+                }
+            }
+        }
+
+        //when every move was tried there's nothing the table could add, and it may even be wrong.
+        let no_more_moves = searched_moves > 0 && searched_moves == legal_moves.len();
+        let value = if no_more_moves {
+            best_value
+        } else {
+            match self.probe_table(board, TableKind::Wdl, Wdl::Draw)? {
+                TableProbe::Value(value) => Wdl::from_value(value),
+                TableProbe::OtherSideToMove => return Err(CORRUPT_TABLE),
+            }
+        };
+
+        if best_value >= value {
+            return Ok((best_value, best_value > Wdl::Draw || no_more_moves));
+        }
+        Ok((value, false))
+    }
+
+    fn search_dtz(&self, board: &mut Board) -> Result<i32, &'static str> {
+        let (wdl, zeroing_is_best) = self.search_wdl(board, true)?;
+        if wdl == Wdl::Draw {
+            return Ok(0);
+        }
+        if zeroing_is_best {
+            return Ok(wdl.dtz_before_zeroing());
+        }
+
+        let sign = wdl.value().signum();
+        match self.probe_table(board, TableKind::Dtz, wdl)? {
+            TableProbe::Value(dtz) => {
+                let fifty_move_adjust = match wdl {
+                    Wdl::CursedWin | Wdl::BlessedLoss => 100,
+                    _ => 0,
+                };
+                return Ok((dtz + fifty_move_adjust) * sign);
+            }
+            TableProbe::OtherSideToMove => {}
+        }
+
+        //the table only has the other side to move, so look one move ahead and take the best of those.
+        let mut min_dtz = i32::MAX;
+        for legal_move in board.legal_moves() {
+            let zeroing = is_capture(board, &legal_move) || is_pawn_move(board, &legal_move);
+            let undo = board.make_move(&legal_move);
+            let opt_dtz = if zeroing {
+                self.search_wdl(board, false)
+                    .map(|(reply_wdl, _)| -reply_wdl.dtz_before_zeroing())
+            } else {
+                self.search_dtz(board).map(|reply_dtz| -reply_dtz)
+            };
+            let mates = is_checkmate(board);
+            board.unmake_move(undo);
+            let mut dtz = opt_dtz?;
+
+            if dtz == 1 && mates {
+                min_dtz = 1;
+            }
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+            if dtz < min_dtz && dtz.signum() == sign {
+                min_dtz = dtz;
+            }
+        }
+        //with no legal moves at all, the side to move has been mated.
+        Ok(if min_dtz == i32::MAX { -1 } else { min_dtz })
+    }
+
+    fn check_probe_allowed(&self, board: &Board) -> Result<(), &'static str> {
+        if board.occupied_bitboard().count_ones() as usize > MAX_TABLEBASE_PIECES {
+            return Err("tablebases only go up to seven pieces");
+        }
+        for king_color in PieceColor::iterator() {
+            if board.has_castling_rights(*king_color, true)
+                || board.has_castling_rights(*king_color, false)
+            {
+                return Err("tablebases don't cover positions where castling is still allowed");
+            }
+        }
+        Ok(())
+    }
+
+    //win, draw or loss for the side to move.
+    pub fn probe_wdl(&self, board: &Board) -> Result<Wdl, &'static str> {
+        self.check_probe_allowed(board)?;
+        Ok(self.search_wdl(&mut board.clone(), false)?.0)
+    }
+
+    //half moves until the next capture or pawn move on the way to the best result, counted from the side to
+    //move's point of view: positive when it's winning, negative when it's losing and 0 for a draw. Wins and losses
+    //the fifty move rule spoils are pushed past 100.
+    pub fn probe_dtz(&self, board: &Board) -> Result<i32, &'static str> {
+        self.check_probe_allowed(board)?;
+        self.search_dtz(&mut board.clone())
+    }
+
+    //every legal move with the result it leads to, best first. Wins come fastest first, losses slowest first.
+    pub fn tablebase_moves(&self, board: &Board) -> Result<Vec<TablebaseMove>, &'static str> {
+        self.check_probe_allowed(board)?;
+        let mut search_board = board.clone();
+        let mut tablebase_moves = Vec::new();
+        for legal_move in board.legal_moves() {
+            let zeroing = is_capture(board, &legal_move) || is_pawn_move(board, &legal_move);
+            let undo = search_board.make_move(&legal_move);
+            let opt_result =
+                self.search_wdl(&mut search_board, false)
+                    .and_then(|(reply_wdl, _)| {
+                        let wdl = reply_wdl.opposite();
+                        let dtz = if zeroing {
+                            wdl.dtz_before_zeroing()
+                        } else {
+                            let reply_dtz = -self.search_dtz(&mut search_board)?;
+                            reply_dtz + reply_dtz.signum()
+                        };
+                        Ok((wdl, dtz))
+                    });
+            let mates = is_checkmate(&search_board);
+            search_board.unmake_move(undo);
+            let (wdl, mut dtz) = opt_result?;
+            if mates {
+                dtz = 1;
+            }
+            tablebase_moves.push(TablebaseMove {
+                chess_move: legal_move,
+                wdl,
+                dtz,
+            });
+        }
+        tablebase_moves
+            .sort_by_key(|tablebase_move| (Reverse(tablebase_move.wdl), tablebase_move.dtz));
+        Ok(tablebase_moves)
+    }
+
+    pub fn best_move(&self, board: &Board) -> Result<Option<TablebaseMove>, &'static str> {
+        Ok(self.tablebase_moves(board)?.first().copied())
+    }
+}
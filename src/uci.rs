@@ -0,0 +1,277 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread::{self, ScopedJoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    board::{Board, MoveResult},
+    fen_parser::digest_string_to_board,
+    moves::Move,
+    pieces::PieceColor,
+    search::{search_with_history_and_progress, SearchLimits, SearchResult},
+};
+
+const ENGINE_NAME: &str = "Ch-ust";
+
+//the limits a GUI can put on one go command. Anything left out means there's no limit of that kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GoLimits {
+    pub opt_depth: Option<u32>,
+    pub opt_move_time: Option<Duration>,
+    pub opt_white_time: Option<Duration>,
+    pub opt_black_time: Option<Duration>,
+    pub opt_white_increment: Option<Duration>,
+    pub opt_black_increment: Option<Duration>,
+    pub opt_moves_to_go: Option<u32>,
+    pub infinite: bool,
+}
+
+impl GoLimits {
+    pub fn parse(arguments: &[&str]) -> GoLimits {
+        let mut limits = GoLimits::default();
+        let mut argument_iter = arguments.iter();
+        while let Some(argument) = argument_iter.next() {
+            //every limit but infinite has a number after it. Ones we can't read are left out.
+            let mut next_number = || {
+                argument_iter
+                    .next()
+                    .and_then(|number| number.parse::<u64>().ok())
+            };
+            match *argument {
+                "depth" => limits.opt_depth = next_number().map(|depth| depth as u32),
+                "movetime" => limits.opt_move_time = next_number().map(Duration::from_millis),
+                "wtime" => limits.opt_white_time = next_number().map(Duration::from_millis),
+                "btime" => limits.opt_black_time = next_number().map(Duration::from_millis),
+                "winc" => limits.opt_white_increment = next_number().map(Duration::from_millis),
+                "binc" => limits.opt_black_increment = next_number().map(Duration::from_millis),
+                "movestogo" => limits.opt_moves_to_go = next_number().map(|moves| moves as u32),
+                "infinite" => limits.infinite = true,
+                _ => {}
+            }
+        }
+        limits
+    }
+
+    pub fn search_limits(&self, side_to_move: PieceColor) -> SearchLimits {
+        SearchLimits {
+            opt_depth: self.opt_depth,
+            opt_time: self.time_budget(side_to_move),
+        }
+    }
+
+    //how long we can think about this move, if there's any limit on it at all.
+    pub fn time_budget(&self, side_to_move: PieceColor) -> Option<Duration> {
+        if self.infinite {
+            return None;
+        }
+        if self.opt_move_time.is_some() {
+            return self.opt_move_time;
+        }
+        let (opt_remaining, opt_increment) = match side_to_move {
+            PieceColor::White => (self.opt_white_time, self.opt_white_increment),
+            PieceColor::Black => (self.opt_black_time, self.opt_black_increment),
+        };
+        //spread what's left over the moves still to come, and keep a little back so we never flag.
+        opt_remaining.map(|remaining| {
+            let moves_to_go = self.opt_moves_to_go.unwrap_or(30).max(1);
+            let share = remaining / moves_to_go + opt_increment.unwrap_or_default() / 2;
+            share.min(remaining.saturating_sub(Duration::from_millis(50)))
+        })
+    }
+}
+
+//UCI writes moves as from square, to square and a lowercase promotion letter. That's how our moves print already.
+pub fn parse_uci_move(board: &Board, move_text: &str) -> Result<Move, &'static str> {
+    board
+        .legal_moves()
+        .into_iter()
+        .find(|legal_move| legal_move.to_string() == move_text)
+        .ok_or("that isn't a legal move in this position")
+}
+
+//the board the moves lead to, and the hash of every position on the way there so the search can see repetitions.
+fn parse_position(arguments: &[&str]) -> Result<(Board, Vec<u64>), &'static str> {
+    let moves_start = arguments
+        .iter()
+        .position(|argument| *argument == "moves")
+        .unwrap_or(arguments.len());
+    let mut board = match arguments.first() {
+        Some(&"startpos") => Board::default(),
+        Some(&"fen") => match digest_string_to_board(arguments[1..moves_start].join(" ")) {
+            Ok(board) => board,
+            Err(_) => return Err("that fen string couldn't be parsed"),
+        },
+        _ => return Err("a position has to be startpos or fen"),
+    };
+    let mut position_history = vec![board.hash_key()];
+    for move_text in arguments.iter().skip(moves_start + 1) {
+        let uci_move = parse_uci_move(&board, move_text)?;
+        match board.move_piece(&uci_move) {
+            MoveResult::WrongTurn | MoveResult::MoveIllegal | MoveResult::EmptySquare => {
+                return Err("the board refused one of the moves");
+            }
+            _ => {}
+        }
+        position_history.push(board.hash_key());
+    }
+    Ok((board, position_history))
+}
+
+fn write_info<W: Write>(output: &mut W, result: &SearchResult) -> io::Result<()> {
+    let score = match result.opt_mate_in() {
+        Some(mate_in) => format!("mate {}", mate_in),
+        None => format!("cp {}", result.score),
+    };
+    let principal_variation = result
+        .principal_variation
+        .iter()
+        .map(|pv_move| pv_move.to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+    writeln!(
+        output,
+        "info depth {} score {} nodes {} pv {}",
+        result.depth, score, result.nodes, principal_variation
+    )?;
+    output.flush()
+}
+
+fn run_search<W: Write>(
+    board: &Board,
+    position_history: &[u64],
+    limits: GoLimits,
+    stop_signal: &AtomicBool,
+    output: &Mutex<&mut W>,
+) -> io::Result<()> {
+    let mut opt_info_error: Option<io::Error> = None;
+    let result = search_with_history_and_progress(
+        board,
+        position_history,
+        limits.search_limits(board.get_turn()),
+        Some(stop_signal),
+        |iteration_result| {
+            if let (None, Ok(mut output)) = (&opt_info_error, output.lock()) {
+                opt_info_error = write_info(&mut *output, iteration_result).err();
+            }
+        },
+    );
+    if let Some(info_error) = opt_info_error {
+        return Err(info_error);
+    }
+
+    //an infinite search doesn't get to answer until the GUI says stop, even if it's run out of things to look at.
+    while limits.infinite && !stop_signal.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    let mut output = match output.lock() {
+        Ok(output) => output,
+        Err(_) => return Err(io::Error::other("the output lock was poisoned")),
+    };
+    match result.opt_best_move {
+        Some(best_move) => writeln!(output, "bestmove {}", best_move)?,
+        //no legal moves left. UCI calls a null move 0000.
+        None => writeln!(output, "bestmove 0000")?,
+    }
+    output.flush()
+}
+
+fn finish_search(
+    opt_search: &mut Option<ScopedJoinHandle<io::Result<()>>>,
+    stop_signal: &AtomicBool,
+    stop_early: bool,
+) -> io::Result<()> {
+    if let Some(search_handle) = opt_search.take() {
+        if stop_early {
+            stop_signal.store(true, Ordering::Relaxed);
+        }
+        match search_handle.join() {
+            Ok(search_outcome) => search_outcome?,
+            Err(_) => return Err(io::Error::other("the search thread panicked")),
+        }
+    }
+    Ok(())
+}
+
+//the search runs on its own thread, so we can keep reading commands like stop and isready while it thinks.
+pub fn run_uci<R: BufRead, W: Write + Send>(input: R, output: &mut W) -> io::Result<()> {
+    let output = Mutex::new(output);
+    let stop_signal = AtomicBool::new(false);
+    let mut board = Board::default();
+    let mut position_history = vec![board.hash_key()];
+
+    thread::scope(|scope| {
+        let mut opt_search: Option<ScopedJoinHandle<io::Result<()>>> = None;
+        let mut searching_infinitely = false;
+        let write_line = |line: &str| -> io::Result<()> {
+            match output.lock() {
+                Ok(mut output) => {
+                    writeln!(output, "{}", line)?;
+                    output.flush()
+                }
+                Err(_) => Err(io::Error::other("the output lock was poisoned")),
+            }
+        };
+
+        for line in input.lines() {
+            let line = line?;
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            let (command, arguments) = match words.split_first() {
+                Some((command, arguments)) => (*command, arguments),
+                None => continue,
+            };
+
+            match command {
+                "uci" => {
+                    write_line(&format!("id name {}", ENGINE_NAME))?;
+                    write_line(&format!("id author the {} authors", ENGINE_NAME))?;
+                    write_line("uciok")?;
+                }
+                "isready" => write_line("readyok")?,
+                "ucinewgame" => {
+                    finish_search(&mut opt_search, &stop_signal, true)?;
+                    board = Board::default();
+                    position_history = vec![board.hash_key()];
+                }
+                "position" => {
+                    finish_search(&mut opt_search, &stop_signal, true)?;
+                    match parse_position(arguments) {
+                        Ok((new_board, new_history)) => {
+                            board = new_board;
+                            position_history = new_history;
+                        }
+                        Err(reason) => write_line(&format!("info string {}", reason))?,
+                    }
+                }
+                "go" => {
+                    finish_search(&mut opt_search, &stop_signal, true)?;
+                    stop_signal.store(false, Ordering::Relaxed);
+                    let limits = GoLimits::parse(arguments);
+                    searching_infinitely = limits.infinite;
+                    let search_board = board.clone();
+                    let search_history = position_history.clone();
+                    let (stop_signal, output) = (&stop_signal, &output);
+                    opt_search = Some(scope.spawn(move || {
+                        run_search(&search_board, &search_history, limits, stop_signal, output)
+                    }));
+                }
+                "stop" => finish_search(&mut opt_search, &stop_signal, true)?,
+                "quit" => {
+                    finish_search(&mut opt_search, &stop_signal, true)?;
+                    return Ok(());
+                }
+                _ => {
+                    //the protocol says to ignore anything we don't understand.
+                }
+            }
+        }
+
+        //the input ran out. A search with a limit gets to finish, an infinite one would never end by itself.
+        finish_search(&mut opt_search, &stop_signal, searching_infinitely)
+    })
+}
@@ -1,8 +1,23 @@
 use crate::{
-    board::{Board, ColumnLetter, Coordinates, MoveResult, Row, SideInformation, Square},
-    fen_parser::ingest_fen_file,
+    bitboard,
+    board::{
+        Board, ColumnLetter, Coordinates, MoveResult, Row, SideInformation, Square,
+        CHESS960_POSITIONS,
+    },
+    evaluation::{evaluate, evaluation_breakdown},
+    fen_parser::{digest_string_to_board, ingest_fen_file, FenError},
+    game::{Game, GameOutcome, GameResult, TerminationReason},
+    interface::parse_square,
+    moves::Move,
+    pgn_parser::{read_pgn_string, PgnError},
     pieces::{Piece, PieceColor, PieceKind},
+    polyglot::{polyglot_key, BookEntry, BookSelection, PolyglotBook},
+    rules::insufficient_mating_material,
+    search::{search, search_with_history, search_with_progress, SearchLimits},
+    tablebase::{Tablebase, Wdl},
+    uci::run_uci,
 };
+use std::fs;
 
 #[test]
 fn test_cloning_board_works() {
@@ -173,7 +188,7 @@ fn test_pawn_single_step() {
     let pawn_from_coords = Coordinates::new(ColumnLetter::A, 2);
     let pawn_to_coords = Coordinates::new(ColumnLetter::A, 3);
 
-    default_board.move_piece(&pawn_from_coords, &pawn_to_coords);
+    default_board.move_piece(&Move::new(pawn_from_coords, pawn_to_coords, None));
 
     let boards_equal = default_board == stepped_board;
     assert!(boards_equal);
@@ -219,7 +234,7 @@ pub fn test_pawn_double_step() {
     let pawn_from_coords = Coordinates::new(ColumnLetter::A, 2);
     let pawn_to_coords = Coordinates::new(ColumnLetter::A, 4);
 
-    default_board.move_piece(&pawn_from_coords, &pawn_to_coords);
+    default_board.move_piece(&Move::new(pawn_from_coords, pawn_to_coords, None));
 
     let boards_equal = default_board == stepped_board;
 
@@ -233,7 +248,7 @@ pub fn test_wrong_side_turn() {
     let pawn_from = Coordinates::new(ColumnLetter::A, 7);
     let pawn_to = Coordinates::new(ColumnLetter::A, 6);
 
-    let move_result = default_board.move_piece(&pawn_from, &pawn_to);
+    let move_result = default_board.move_piece(&Move::new(pawn_from, pawn_to, None));
 
     assert_eq!(move_result, MoveResult::WrongTurn);
 }
@@ -279,10 +294,11 @@ pub fn test_en_passant_should_pass() {
 
     let mut current_turn_board = previous_turn_board.clone();
 
-    let move_result = current_turn_board.move_piece(
-        &current_black_pawn_square_coords,
-        &black_pawn_jump_target_coords,
-    );
+    let move_result = current_turn_board.move_piece(&Move::new(
+        current_black_pawn_square_coords,
+        black_pawn_jump_target_coords,
+        None,
+    ));
     assert!(move_result == MoveResult::CompletedSafely);
 
     // we have moved the pawn forward. Now lets try to en passant
@@ -294,8 +310,11 @@ pub fn test_en_passant_should_pass() {
         letter: ColumnLetter::E,
         number: 6,
     };
-    let en_passant_move_result =
-        current_turn_board.move_piece(&white_pawn_from_square, &white_pawn_to_square);
+    let en_passant_move_result = current_turn_board.move_piece(&Move::new(
+        white_pawn_from_square,
+        white_pawn_to_square,
+        None,
+    ));
 
     assert!(en_passant_move_result == MoveResult::CompletedSafely);
 
@@ -306,11 +325,11 @@ pub fn test_en_passant_should_pass() {
 fn test_basic_read() {
     let result = ingest_fen_file("./src/fenFiles/default_board.fen");
     match result {
-        Some(board) => {
+        Ok(board) => {
             let default_board = Board::default();
             assert!(board == default_board);
         }
-        None => {
+        Err(_) => {
             assert!(false)
         }
     }
@@ -320,35 +339,37 @@ fn test_basic_read() {
 fn test_read_board_with_pawn_steps() {
     let mut origin_board = Board::default();
 
-    origin_board.move_piece(
-        &Coordinates {
+    origin_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::C,
             number: 2,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::C,
             number: 4,
         },
-    );
-    origin_board.move_piece(
-        &Coordinates {
+        None,
+    ));
+    origin_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::D,
             number: 7,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::D,
             number: 5,
         },
-    );
+        None,
+    ));
 
     let opt_pulled_board = ingest_fen_file("./src/fenFiles/two_stepped_pawns.fen");
 
     match opt_pulled_board {
-        Some(pulled_board) => {
+        Ok(pulled_board) => {
             println!("{}", origin_board.generate_fen_string());
             assert!(origin_board == pulled_board);
         }
-        None => {
+        Err(_) => {
             assert!(false)
         }
     }
@@ -360,78 +381,1825 @@ pub fn test_ingesting_fen_with_two_taken_pieces() {
 
     let mut moving_board = Board::default();
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::C,
             number: 2,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::C,
             number: 4,
         },
-    );
+        None,
+    ));
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::D,
             number: 7,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::D,
             number: 5,
         },
-    );
+        None,
+    ));
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::C,
             number: 4,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::D,
             number: 5,
         },
-    );
+        None,
+    ));
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::C,
             number: 8,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::F,
             number: 5,
         },
-    );
+        None,
+    ));
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::D,
             number: 5,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::D,
             number: 6,
         },
-    );
+        None,
+    ));
 
-    moving_board.move_piece(
-        &Coordinates {
+    moving_board.move_piece(&Move::new(
+        Coordinates {
             letter: ColumnLetter::F,
             number: 5,
         },
-        &Coordinates {
+        Coordinates {
             letter: ColumnLetter::B,
             number: 1,
         },
-    );
+        None,
+    ));
 
     match opt_pulled_board {
-        Some(pulled_board) => {
+        Ok(pulled_board) => {
             assert!(pulled_board == moving_board);
         }
-        None => {
+        Err(_) => {
             assert!(false);
         }
     }
 }
+
+#[test]
+pub fn test_pawn_must_name_promotion() {
+    let mut promotion_board =
+        digest_string_to_board("1r2k3/P7/8/8/8/8/8/3QK3 w - - 0 1".to_string()).unwrap();
+
+    let pawn_from = Coordinates::new(ColumnLetter::A, 7);
+    let pawn_to = Coordinates::new(ColumnLetter::A, 8);
+
+    let move_result = promotion_board.move_piece(&Move::new(pawn_from, pawn_to, None));
+    assert_eq!(move_result, MoveResult::MoveIllegal);
+
+    let move_result =
+        promotion_board.move_piece(&Move::new(pawn_from, pawn_to, Some(PieceKind::King)));
+    assert_eq!(move_result, MoveResult::MoveIllegal);
+
+    let move_result =
+        promotion_board.move_piece(&Move::new(pawn_from, pawn_to, Some(PieceKind::Knight)));
+    assert_eq!(move_result, MoveResult::CompletedSafely);
+    assert_eq!(
+        promotion_board.retreive_square(&pawn_to),
+        Ok(Square::Full(Piece {
+            color: PieceColor::White,
+            kind: PieceKind::Knight,
+        }))
+    );
+}
+
+#[test]
+pub fn test_only_pawns_on_last_rank_promote() {
+    let mut promotion_board =
+        digest_string_to_board("1r2k3/P7/8/8/8/8/8/3QK3 w - - 0 1".to_string()).unwrap();
+
+    let move_result = promotion_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::D, 1),
+        Coordinates::new(ColumnLetter::D, 2),
+        Some(PieceKind::Queen),
+    ));
+    assert_eq!(move_result, MoveResult::MoveIllegal);
+}
+
+#[test]
+pub fn test_taking_promoted_piece_counts_as_pawn() {
+    let mut promotion_board =
+        digest_string_to_board("1r2k3/P7/8/8/8/8/8/3QK3 w - - 0 1".to_string()).unwrap();
+
+    promotion_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::A, 7),
+        Coordinates::new(ColumnLetter::A, 8),
+        Some(PieceKind::Queen),
+    ));
+    let move_result = promotion_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::B, 8),
+        Coordinates::new(ColumnLetter::A, 8),
+        None,
+    ));
+    assert_eq!(move_result, MoveResult::CompletedSafely);
+
+    //counting the pieces from scratch should agree with what we tallied move by move.
+    let recounted_board = digest_string_to_board(promotion_board.generate_fen_string()).unwrap();
+    assert!(promotion_board == recounted_board);
+}
+
+#[test]
+pub fn test_default_board_has_twenty_legal_moves() {
+    let default_board = Board::default();
+
+    assert_eq!(default_board.legal_moves().len(), 20);
+}
+
+#[test]
+pub fn test_legal_moves_include_castling() {
+    let castling_board = digest_string_to_board(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    let legal_moves = castling_board.legal_moves();
+
+    assert_eq!(legal_moves.len(), 48);
+    assert!(legal_moves.contains(&Move::new(
+        Coordinates::new(ColumnLetter::E, 1),
+        Coordinates::new(ColumnLetter::G, 1),
+        None,
+    )));
+    assert!(legal_moves.contains(&Move::new(
+        Coordinates::new(ColumnLetter::E, 1),
+        Coordinates::new(ColumnLetter::C, 1),
+        None,
+    )));
+}
+
+#[test]
+pub fn test_legal_moves_include_en_passant() {
+    let passant_board = ingest_fen_file("./src/fenFiles/two_stepped_pawns.fen").unwrap();
+    let mut legal_moves = passant_board.legal_moves();
+
+    //white has a pawn on c4 and black has just jumped to d5, so c4 can take d5 normally but there's no passant for white yet.
+    assert!(legal_moves.contains(&Move::new(
+        Coordinates::new(ColumnLetter::C, 4),
+        Coordinates::new(ColumnLetter::D, 5),
+        None,
+    )));
+
+    let mut stepped_board = passant_board.clone();
+    stepped_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::C, 4),
+        Coordinates::new(ColumnLetter::C, 5),
+        None,
+    ));
+    stepped_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::B, 7),
+        Coordinates::new(ColumnLetter::B, 5),
+        None,
+    ));
+    legal_moves = stepped_board.legal_moves();
+
+    assert!(legal_moves.contains(&Move::new(
+        Coordinates::new(ColumnLetter::C, 5),
+        Coordinates::new(ColumnLetter::B, 6),
+        None,
+    )));
+}
+
+#[test]
+pub fn test_legal_moves_include_every_promotion() {
+    let promotion_board =
+        digest_string_to_board("4k3/P7/8/8/8/8/8/4K3 w - - 0 1".to_string()).unwrap();
+    let promotion_moves = promotion_board
+        .legal_moves()
+        .into_iter()
+        .filter(|legal_move| legal_move.from == Coordinates::new(ColumnLetter::A, 7))
+        .collect::<Vec<Move>>();
+
+    assert_eq!(promotion_moves.len(), 4);
+    for promotion_kind in [
+        PieceKind::Queen,
+        PieceKind::Rook,
+        PieceKind::Bishop,
+        PieceKind::Knight,
+    ] {
+        assert!(promotion_moves.contains(&Move::new(
+            Coordinates::new(ColumnLetter::A, 7),
+            Coordinates::new(ColumnLetter::A, 8),
+            Some(promotion_kind),
+        )));
+    }
+}
+
+#[test]
+pub fn test_pinned_piece_still_gives_check() {
+    //the black bishop on b4 is pinned to its king on a3 by the bishop on d6, but it's still checking the white king.
+    let pinned_board =
+        digest_string_to_board("8/8/3B4/8/1b6/k7/3K4/8 w - - 0 1".to_string()).unwrap();
+
+    assert!(pinned_board.is_king_in_danger(PieceColor::White));
+}
+
+#[test]
+pub fn test_set_square_round_trips_through_bitboards() {
+    let mut board = Board::default();
+    let target = Coordinates::new(ColumnLetter::D, 4);
+    let black_knight = Piece {
+        color: PieceColor::Black,
+        kind: PieceKind::Knight,
+    };
+    let knight = Square::Full(black_knight);
+
+    board.set_square(&target, knight);
+    assert!(board.retreive_square(&target).unwrap() == knight);
+    assert_eq!(
+        board.piece_bitboard(black_knight),
+        (1u64 << 1) << 56 | (1u64 << 6) << 56 | 1u64 << 27
+    );
+
+    board.set_square(&target, Square::Empty);
+    assert!(board.retreive_square(&target).unwrap() == Square::Empty);
+    assert!(board == Board::default());
+}
+
+#[test]
+pub fn test_sliding_attacks_stop_at_blockers() {
+    //a rook on a1 with a blocker on a4 sees a2, a3, a4 and the whole first rank.
+    let blocker = 1u64 << 24;
+    let rook_attacks = bitboard::rook_attacks(0, blocker);
+    assert_eq!(rook_attacks, 0xfe | 1u64 << 8 | 1u64 << 16 | blocker);
+
+    //a bishop on d4 on an empty board sees 13 squares.
+    assert_eq!(bitboard::bishop_attacks(27, 0).count_ones(), 13);
+    assert_eq!(bitboard::knight_attacks(0), 1u64 << 10 | 1u64 << 17);
+}
+
+#[test]
+pub fn test_unmake_move_restores_every_position_exactly() {
+    //kiwipete has castling and captures, the second has en passant, and the third a capturing promotion onto a rook.
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/1P6/8/8/8/8/8/4K3 w kq - 0 1",
+        "4k3/8/8/8/8/8/8/RK5R w AH - 0 1",
+    ] {
+        let mut board = digest_string_to_board(fen.to_string()).unwrap();
+        let original_board = board.clone();
+        for legal_move in original_board.legal_moves() {
+            let mut moved_board = original_board.clone();
+            moved_board.move_piece(&legal_move);
+
+            let undo = board.make_move(&legal_move);
+            assert!(board == moved_board);
+            board.unmake_move(undo);
+            assert!(board == original_board);
+        }
+    }
+}
+
+#[test]
+pub fn test_san_round_trips_every_legal_move() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/1P6/8/8/8/8/8/4K3 w kq - 0 1",
+        "4k3/8/8/8/8/8/8/RK5R w AH - 0 1",
+    ] {
+        let board = digest_string_to_board(fen.to_string()).unwrap();
+        for legal_move in board.legal_moves() {
+            let san = legal_move.to_san(&board);
+            assert_eq!(board.parse_san(&san), Ok(legal_move), "{}", san);
+        }
+    }
+}
+
+#[test]
+pub fn test_san_generation() {
+    let kiwipete = digest_string_to_board(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    let san_of = |board: &Board, from: &str, to: &str, opt_promotion: Option<PieceKind>| {
+        Move::new(
+            parse_square(from).unwrap(),
+            parse_square(to).unwrap(),
+            opt_promotion,
+        )
+        .to_san(board)
+    };
+
+    assert_eq!(san_of(&kiwipete, "e1", "g1", None), "O-O");
+    assert_eq!(san_of(&kiwipete, "e1", "c1", None), "O-O-O");
+    assert_eq!(san_of(&kiwipete, "e5", "f7", None), "Nxf7");
+    assert_eq!(san_of(&kiwipete, "d5", "e6", None), "dxe6");
+    assert_eq!(san_of(&kiwipete, "g2", "h3", None), "gxh3");
+
+    //both knights and both rooks can reach the same squares here.
+    let twins_board =
+        digest_string_to_board("4k3/8/8/8/8/8/R6R/1N2KN2 w - - 0 1".to_string()).unwrap();
+    assert_eq!(san_of(&twins_board, "b1", "d2", None), "Nbd2");
+    assert_eq!(san_of(&twins_board, "a2", "d2", None), "Rad2");
+    assert_eq!(san_of(&twins_board, "f1", "g3", None), "Ng3");
+
+    let stacked_board =
+        digest_string_to_board("4k3/8/8/8/R7/8/R7/4K3 w - - 0 1".to_string()).unwrap();
+    assert_eq!(san_of(&stacked_board, "a2", "a3", None), "R2a3");
+
+    let promotion_board =
+        digest_string_to_board("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1".to_string()).unwrap();
+    assert_eq!(
+        san_of(&promotion_board, "b7", "a8", Some(PieceKind::Queen)),
+        "bxa8=Q+"
+    );
+    assert_eq!(
+        san_of(&promotion_board, "b7", "b8", Some(PieceKind::Knight)),
+        "b8=N"
+    );
+
+    let mate_board =
+        digest_string_to_board("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1".to_string()).unwrap();
+    assert_eq!(san_of(&mate_board, "a1", "a8", None), "Ra8#");
+}
+
+#[test]
+pub fn test_parse_san_input() {
+    let default_board = Board::default();
+    assert_eq!(
+        default_board.parse_san("Nf3"),
+        Ok(Move::new(
+            Coordinates::new(ColumnLetter::G, 1),
+            Coordinates::new(ColumnLetter::F, 3),
+            None,
+        ))
+    );
+    assert_eq!(
+        default_board.parse_san("e4"),
+        Ok(Move::new(
+            Coordinates::new(ColumnLetter::E, 2),
+            Coordinates::new(ColumnLetter::E, 4),
+            None,
+        ))
+    );
+    assert!(default_board.parse_san("e5").is_err());
+    assert!(default_board.parse_san("O-O").is_err());
+    assert!(default_board.parse_san("Zz9").is_err());
+
+    let twins_board =
+        digest_string_to_board("4k3/8/8/8/8/8/R6R/1N2KN2 w - - 0 1".to_string()).unwrap();
+    assert_eq!(twins_board.parse_san("Nd2"), Err("that move is ambiguous"));
+    assert!(twins_board.parse_san("Nfd2").is_ok());
+
+    let promotion_board =
+        digest_string_to_board("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1".to_string()).unwrap();
+    assert_eq!(
+        promotion_board.parse_san("bxa8Q"),
+        promotion_board.parse_san("bxa8=Q+")
+    );
+    assert!(promotion_board.parse_san("b8").is_err());
+    assert!(promotion_board.parse_san("b8=K").is_err());
+}
+
+#[test]
+pub fn test_game_exports_pgn() {
+    let mut game = Game::new();
+    for san in ["f3", "e5", "g4", "Qh4#"] {
+        let next_move = game.current_board().parse_san(san).unwrap();
+        game.play_move(&next_move);
+    }
+    game.set_tag("White", "Patzer \"the Great\"");
+
+    assert_eq!(game.get_tag("Result"), Some("0-1"));
+    assert_eq!(
+        game.to_pgn(),
+        "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+         [White \"Patzer \\\"the Great\\\"\"]\n[Black \"?\"]\n[Result \"0-1\"]\n\
+         [Termination \"normal\"]\n\n1. f3 e5 2. g4 Qh4# {Black wins by checkmate} 0-1\n\n"
+    );
+}
+
+#[test]
+pub fn test_game_from_position_records_fen() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+    let mut game = Game::from_board(digest_string_to_board(fen.to_string()).unwrap());
+    let next_move = game.current_board().parse_san("Kd7").unwrap();
+    game.play_move(&next_move);
+
+    let illegal_move = game.current_board().parse_san("e5");
+    assert!(illegal_move.is_err());
+
+    let pgn = game.to_pgn();
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n"));
+    assert!(pgn.ends_with("\n\n12... Kd7 *\n\n"));
+}
+
+#[test]
+pub fn test_pgn_reader_plays_mainline_of_each_game() {
+    let pgn_text = "% this line is for some other program\n\
+        [Event \"Casual \\\"blitz\\\"\"]\n\
+        [White \"Anderssen\"]\n\
+        [Black \"Kieseritzky\"]\n\
+        [Result \"1-0\"]\n\
+        \n\
+        1. e4 {the king's pawn} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6 ; rest of line\n\
+        3. Bb5!? a6 1-0\n\
+        \n\
+        [Event \"Second\"]\n\
+        [SetUp \"1\"]\n\
+        [FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n\
+        \n\
+        12... Kd7 13. e4 *\n";
+
+    let games = read_pgn_string(pgn_text).collect::<Vec<Result<Game, PgnError>>>();
+    assert_eq!(games.len(), 2);
+
+    let first_game = games[0].as_ref().unwrap();
+    assert_eq!(first_game.get_tag("Event"), Some("Casual \"blitz\""));
+    assert_eq!(first_game.get_tag("White"), Some("Anderssen"));
+    assert_eq!(first_game.get_tag("Result"), Some("1-0"));
+    assert_eq!(first_game.moves().len(), 6);
+    assert_eq!(
+        first_game.current_board().generate_fen_string(),
+        "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4"
+    );
+
+    let second_game = games[1].as_ref().unwrap();
+    assert_eq!(second_game.moves().len(), 2);
+    assert_eq!(second_game.get_tag("Result"), Some("*"));
+    assert_eq!(second_game.current_board().get_full_turns(), 13);
+}
+
+#[test]
+pub fn test_pgn_reader_reports_ply_and_token() {
+    let pgn_text = "[Event \"Broken\"]\n\n1. e4 e5 2. Ke3 Nc6 *\n\n[Event \"Fine\"]\n\n1. d4 *\n";
+    let mut pgn_reader = read_pgn_string(pgn_text);
+
+    match pgn_reader.next() {
+        Some(Err(pgn_error)) => {
+            assert_eq!(pgn_error.ply, 3);
+            assert_eq!(pgn_error.token, "Ke3");
+        }
+        _ => panic!("the broken game should have come back as an error"),
+    }
+
+    //one bad game doesn't spoil the rest of the file.
+    let fine_game = pgn_reader.next().unwrap().unwrap();
+    assert_eq!(fine_game.get_tag("Event"), Some("Fine"));
+    assert_eq!(fine_game.moves().len(), 1);
+    assert!(pgn_reader.next().is_none());
+}
+
+#[test]
+pub fn test_pgn_reader_recovers_from_tokenizer_errors() {
+    let pgn_text = "[Event \"Garbled\"]\n\n1. e4 & e5 *\n\n[Event \"Fine\"]\n\n1. d4 *\n\n[Event \"Cut off\"]\n\n1. c4 {never closed";
+    let mut pgn_reader = read_pgn_string(pgn_text);
+
+    match pgn_reader.next() {
+        Some(Err(pgn_error)) => {
+            assert_eq!(pgn_error.ply, 2);
+            assert_eq!(pgn_error.token, "&");
+        }
+        _ => panic!("the garbled game should have come back as an error"),
+    }
+
+    let fine_game = pgn_reader.next().unwrap().unwrap();
+    assert_eq!(fine_game.get_tag("Event"), Some("Fine"));
+
+    match pgn_reader.next() {
+        Some(Err(pgn_error)) => {
+            assert_eq!(pgn_error.token, "{never closed");
+            assert_eq!(pgn_error.reason, "the file ended inside a comment");
+        }
+        _ => panic!("the unterminated comment should have come back as an error"),
+    }
+    assert!(pgn_reader.next().is_none());
+}
+
+#[test]
+pub fn test_pgn_export_reads_back_in() {
+    let mut game = Game::new();
+    for san in [
+        "e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "d4", "c6", "Nf3", "Bg4",
+    ] {
+        let next_move = game.current_board().parse_san(san).unwrap();
+        game.play_move(&next_move);
+    }
+
+    let read_game = read_pgn_string(&game.to_pgn()).next().unwrap().unwrap();
+    assert_eq!(read_game.moves(), game.moves());
+    assert!(read_game.current_board() == game.current_board());
+}
+
+#[test]
+pub fn test_fen_errors_name_the_field_and_position() {
+    let fen_error = |fen: &str| match digest_string_to_board(fen.to_string()) {
+        Ok(_) => panic!("{} should not have parsed", fen),
+        Err(fen_error) => fen_error,
+    };
+
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"),
+        FenError::WrongFieldCount {
+            text: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -".to_string(),
+            found: 4,
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::PiecePlacement {
+            text: "rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR".to_string(),
+            position: 0,
+            reason: "there should be exactly eight ranks separated by /",
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/ppxppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::PiecePlacement {
+            text: "ppxppppp".to_string(),
+            position: 11,
+            reason: "that isn't a piece letter",
+        }
+    );
+    assert!(matches!(
+        fen_error("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::PiecePlacement { position: 18, .. }
+    ));
+    assert!(matches!(
+        fen_error("rnbqkbnr/pppppppp/7/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::PiecePlacement { position: 18, .. }
+    ));
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"),
+        FenError::SideToMove {
+            text: "x".to_string(),
+            position: 44,
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQxq - 0 1"),
+        FenError::Castling {
+            text: "KQxq".to_string(),
+            position: 48,
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1"),
+        FenError::EnPassant {
+            text: "e9".to_string(),
+            position: 51,
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1"),
+        FenError::HalfMove {
+            text: "x".to_string(),
+            position: 53,
+        }
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0"),
+        FenError::FullMove {
+            text: "0".to_string(),
+            position: 55,
+        }
+    );
+    assert!(matches!(
+        ingest_fen_file("./src/fenFiles/garbage_fen_file.fen"),
+        Err(FenError::PiecePlacement { position: 0, .. })
+    ));
+    assert!(matches!(
+        ingest_fen_file("./src/fenFiles/not_a_file.fen"),
+        Err(FenError::Unreadable { .. })
+    ));
+}
+
+#[test]
+pub fn test_fen_validation_rejects_impossible_positions() {
+    let fen_error = |fen: &str| match digest_string_to_board(fen.to_string()) {
+        Ok(_) => panic!("{} should not have parsed", fen),
+        Err(fen_error) => fen_error,
+    };
+
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::KingCount {
+            color: PieceColor::Black,
+            found: 0,
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        FenError::KingCount {
+            color: PieceColor::White,
+            found: 2,
+        }
+    );
+    assert_eq!(
+        fen_error("4k2P/8/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::PawnOnBackRank {
+            square: Coordinates::new(ColumnLetter::H, 8),
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/p3K3 b - - 0 1"),
+        FenError::PawnOnBackRank {
+            square: Coordinates::new(ColumnLetter::A, 1),
+        }
+    );
+    //white to move, but it's black's king that's in check from the rook.
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4RK2 w - - 0 1"),
+        FenError::SideNotToMoveInCheck {
+            color: PieceColor::Black,
+        }
+    );
+    assert_eq!(
+        fen_error("r3k3/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        FenError::CastlingWithoutKingAndRook {
+            text: "k".to_string(),
+            position: 27,
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/R4K2 w K - 0 1"),
+        FenError::CastlingWithoutKingAndRook {
+            text: "K".to_string(),
+            position: 23,
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/R3K2R w KH - 0 1"),
+        FenError::Castling {
+            text: "KH".to_string(),
+            position: 25,
+        }
+    );
+    //the en passant square has to be right behind a pawn that could have just stepped two squares.
+    assert!(matches!(
+        fen_error("4k3/8/8/8/4P3/8/8/4K3 b - e4 0 1"),
+        FenError::ImpossibleEnPassant { .. }
+    ));
+    assert!(matches!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 b - e3 0 1"),
+        FenError::ImpossibleEnPassant { .. }
+    ));
+    assert!(matches!(
+        fen_error("4k3/3p4/8/3p4/8/8/8/4K3 w - d6 0 1"),
+        FenError::ImpossibleEnPassant { .. }
+    ));
+
+    //and the king squares come from where the kings really are.
+    let moved_kings = digest_string_to_board("8/8/8/3k4/8/8/8/K7 b - c3 0 1".to_string());
+    assert!(moved_kings.is_err());
+    let moved_kings =
+        digest_string_to_board("8/8/8/3k4/2P5/8/8/K7 b - c3 0 1".to_string()).unwrap();
+    assert!(moved_kings.legal_moves().len() == 8);
+}
+
+#[test]
+pub fn test_repetition_claims_and_fivefold_draw() {
+    let mut game = Game::new();
+    let knight_dance = ["Nf3", "Nf6", "Ng1", "Ng8"];
+    let mut last_result = MoveResult::CompletedSafely;
+
+    for round in 0..4 {
+        for san in knight_dance {
+            assert!(last_result != MoveResult::FivefoldRepetition);
+            let next_move = game.current_board().parse_san(san).unwrap();
+            last_result = game.play_move(&next_move);
+        }
+        //the start position has now come up round + 2 times. The fifth time ends the game, so there's nothing left
+        //to claim.
+        assert_eq!(game.repetition_count(), round + 2);
+        assert_eq!(
+            game.can_claim_threefold_repetition(),
+            (3..5).contains(&(round + 2))
+        );
+    }
+
+    assert_eq!(last_result, MoveResult::FivefoldRepetition);
+    assert_eq!(game.get_tag("Result"), Some("1/2-1/2"));
+}
+
+#[test]
+pub fn test_hash_key_ignores_passant_that_cant_be_taken() {
+    let mut stepped_board = Board::default();
+    stepped_board.move_piece(&Move::new(
+        Coordinates::new(ColumnLetter::E, 2),
+        Coordinates::new(ColumnLetter::E, 4),
+        None,
+    ));
+    let unstepped_board = digest_string_to_board(
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    assert!(stepped_board.get_opt_passant_square().is_some());
+    assert_eq!(stepped_board.hash_key(), unstepped_board.hash_key());
+
+    //a black pawn on d4 could take, so this time the en passant square counts.
+    let takeable_board = digest_string_to_board(
+        "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string(),
+    )
+    .unwrap();
+    let untakeable_board = digest_string_to_board(
+        "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    assert!(takeable_board.hash_key() != untakeable_board.hash_key());
+
+    //the pawn on d4 is pinned to its king, so it can't take en passant and the square doesn't count.
+    let pinned_board =
+        digest_string_to_board("3k4/8/8/8/3pP3/8/8/3RK3 b - e3 0 1".to_string()).unwrap();
+    let unpinned_board =
+        digest_string_to_board("3k4/8/8/8/3pP3/8/8/3RK3 b - - 0 1".to_string()).unwrap();
+    assert_eq!(pinned_board.hash_key(), unpinned_board.hash_key());
+    let mut pinned_game = Game::from_board(pinned_board);
+    for san in ["Kd7", "Kf2", "Kd8", "Ke1"] {
+        let next_move = pinned_game.current_board().parse_san(san).unwrap();
+        pinned_game.play_move(&next_move);
+    }
+    assert_eq!(pinned_game.repetition_count(), 2);
+
+    let mut claimed_game = Game::new();
+    assert!(!claimed_game.claim_threefold_repetition());
+    assert_eq!(claimed_game.get_tag("Result"), Some("*"));
+}
+
+#[test]
+pub fn test_hash_key_matches_a_fresh_board_after_every_move() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/1P6/8/8/8/8/8/4K3 w kq - 0 1",
+        "r3k2r/8/8/8/3p4/8/4P3/R3K2R w KQkq - 0 1",
+    ] {
+        let mut board = digest_string_to_board(fen.to_string()).unwrap();
+        let original_hash = board.hash_key();
+        for legal_move in board.clone().legal_moves() {
+            let undo = board.make_move(&legal_move);
+            let fresh_board = digest_string_to_board(board.generate_fen_string()).unwrap();
+            assert_eq!(board.hash_key(), fresh_board.hash_key(), "{}", legal_move);
+            board.unmake_move(undo);
+            assert_eq!(board.hash_key(), original_hash);
+        }
+    }
+}
+
+#[test]
+pub fn test_hash_key_sees_transpositions() {
+    let play_line = |line: &[&str]| {
+        let mut board = Board::default();
+        for san in line {
+            let next_move = board.parse_san(san).unwrap();
+            board.move_piece(&next_move);
+        }
+        board
+    };
+
+    let knights_first = play_line(&["Nf3", "Nf6", "Nc3"]);
+    let other_knight_first = play_line(&["Nc3", "Nf6", "Nf3"]);
+    assert_eq!(knights_first.hash_key(), other_knight_first.hash_key());
+    assert!(knights_first.hash_key() != Board::default().hash_key());
+
+    //the same pieces with the other side to move, or with a castling right gone, are different positions.
+    let black_to_move = play_line(&["Nf3", "Nf6", "Ng1"]);
+    let white_to_move = play_line(&["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert!(black_to_move.hash_key() != white_to_move.hash_key());
+    assert_eq!(white_to_move.hash_key(), Board::default().hash_key());
+
+    let rook_shuffled = play_line(&["Nf3", "Nf6", "Rg1", "Ng8", "Rh1", "Nf6", "Ng1", "Ng8"]);
+    assert!(rook_shuffled.hash_key() != Board::default().hash_key());
+    let mut rights_removed = Board::default();
+    rights_removed.remove_castling_rights(PieceColor::White, true);
+    assert_eq!(rook_shuffled.hash_key(), rights_removed.hash_key());
+}
+
+#[test]
+pub fn test_insufficient_mating_material() {
+    let is_dead =
+        |fen: &str| insufficient_mating_material(&digest_string_to_board(fen.to_string()).unwrap());
+
+    assert!(is_dead("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+    assert!(is_dead("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    assert!(is_dead("4k3/8/8/8/8/8/8/4K1n1 w - - 0 1"));
+    //every bishop on a dark square, no matter how many or whose.
+    assert!(is_dead("4k3/8/8/8/8/8/8/2B1K1b1 w - - 0 1"));
+    assert!(is_dead("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+
+    assert!(!is_dead("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1"));
+    assert!(!is_dead("4k3/8/8/8/8/8/8/1N2K1n1 w - - 0 1"));
+    assert!(!is_dead("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1"));
+    assert!(!is_dead("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+    assert!(!is_dead("4k3/8/8/8/8/8/8/R3K3 w - - 0 1"));
+}
+
+#[test]
+pub fn test_taking_the_last_mating_piece_draws() {
+    let mut game = Game::from_board(
+        digest_string_to_board("4k3/8/8/8/8/8/3r4/4K3 w - - 0 1".to_string()).unwrap(),
+    );
+    let take_rook = game.current_board().parse_san("Kxd2").unwrap();
+    assert_eq!(game.play_move(&take_rook), MoveResult::InsufficientMaterial);
+    assert_eq!(game.get_tag("Result"), Some("1/2-1/2"));
+
+    //a lone knight can't mate even when it checks, but a queen can.
+    let mut board = digest_string_to_board("4k3/8/8/8/8/8/3N4/4K3 w - - 0 1".to_string()).unwrap();
+    let knight_check = board.parse_san("Nf3").unwrap();
+    assert_eq!(
+        board.move_piece(&knight_check),
+        MoveResult::InsufficientMaterial
+    );
+    let mut board = digest_string_to_board("4k3/8/8/8/8/8/3Q4/4K3 w - - 0 1".to_string()).unwrap();
+    let queen_check = board.parse_san("Qd7").unwrap();
+    assert_eq!(board.move_piece(&queen_check), MoveResult::BlackKingChecked);
+}
+
+#[test]
+pub fn test_fifty_and_seventy_five_move_rules() {
+    //the fiftieth quiet move doesn't end anything by itself, it lets either player claim.
+    let mut game = Game::from_board(
+        digest_string_to_board("4k3/8/8/8/8/8/8/R3K3 w - - 99 80".to_string()).unwrap(),
+    );
+    assert!(!game.can_claim_fifty_move_rule());
+    let rook_move = game.current_board().parse_san("Rb1").unwrap();
+    assert_eq!(game.play_move(&rook_move), MoveResult::CompletedSafely);
+    assert!(game.can_claim_fifty_move_rule());
+    assert!(game.claim_fifty_move_rule());
+    assert_eq!(game.get_tag("Result"), Some("1/2-1/2"));
+
+    let mut board =
+        digest_string_to_board("4k3/8/8/8/8/8/8/R3K3 w - - 149 100".to_string()).unwrap();
+    let rook_move = board.parse_san("Rb1").unwrap();
+    assert_eq!(
+        board.move_piece(&rook_move),
+        MoveResult::SeventyFiveMoveRule
+    );
+
+    //mate on the seventy fifth move still wins.
+    let mut board =
+        digest_string_to_board("6k1/5ppp/8/8/8/8/8/R3K3 w - - 149 100".to_string()).unwrap();
+    let back_rank_mate = board.parse_san("Ra8").unwrap();
+    assert_eq!(
+        board.move_piece(&back_rank_mate),
+        MoveResult::BlackKingCheckmated
+    );
+}
+
+#[test]
+pub fn test_game_outcomes() {
+    let mut game = Game::new();
+    for san in ["f3", "e5", "g4", "Qh4#"] {
+        let next_move = game.current_board().parse_san(san).unwrap();
+        game.play_move(&next_move);
+    }
+    let fools_mate = game.outcome().unwrap();
+    assert_eq!(
+        fools_mate,
+        GameOutcome {
+            result: GameResult::BlackWins,
+            reason: TerminationReason::Checkmate
+        }
+    );
+    assert_eq!(fools_mate.winner(), Some(PieceColor::Black));
+    assert_eq!(fools_mate.to_string(), "Black wins by checkmate");
+
+    //nothing that comes after the mate can change the result.
+    game.resign(PieceColor::Black);
+    game.agree_to_draw();
+    game.lose_on_time(PieceColor::Black);
+    assert!(!game.claim_threefold_repetition());
+    assert_eq!(game.outcome(), Some(fools_mate));
+    assert_eq!(game.get_tag("Result"), Some("0-1"));
+    assert_eq!(game.get_tag("Termination"), Some("normal"));
+
+    let mut game = Game::new();
+    assert!(game.outcome().is_none());
+    game.resign(PieceColor::White);
+    assert_eq!(
+        game.outcome(),
+        Some(GameOutcome::win_for(
+            PieceColor::Black,
+            TerminationReason::Resignation
+        ))
+    );
+    assert_eq!(game.get_tag("Result"), Some("0-1"));
+    //and once it's over, moves don't go on the board or the record any more.
+    let next_move = game.current_board().parse_san("e4").unwrap();
+    assert_eq!(game.play_move(&next_move), MoveResult::MoveIllegal);
+    assert!(game.moves().is_empty());
+    assert_eq!(game.position_history().len(), 1);
+    assert!(game.current_board() == &Board::default());
+
+    let mut game = Game::new();
+    game.agree_to_draw();
+    assert_eq!(
+        game.outcome(),
+        Some(GameOutcome::draw(TerminationReason::Agreement))
+    );
+    assert_eq!(game.get_tag("Result"), Some("1/2-1/2"));
+
+    //flagging against a bare king is a draw, otherwise it's a loss.
+    let mut game = Game::new();
+    game.lose_on_time(PieceColor::Black);
+    assert_eq!(game.get_tag("Result"), Some("1-0"));
+    assert_eq!(game.get_tag("Termination"), Some("time forfeit"));
+    let mut game = Game::from_board(
+        digest_string_to_board("4k3/8/8/8/8/8/8/R3K3 w - - 0 1".to_string()).unwrap(),
+    );
+    game.lose_on_time(PieceColor::White);
+    assert_eq!(
+        game.outcome(),
+        Some(GameOutcome::draw(TerminationReason::Timeout))
+    );
+}
+
+#[test]
+pub fn test_draw_offers() {
+    let mut game = Game::new();
+    let play = |game: &mut Game, san: &str| {
+        let next_move = game.current_board().parse_san(san).unwrap();
+        game.play_move(&next_move);
+    };
+
+    //white offers and then moves. Black answers by moving, which lets the offer lapse.
+    assert!(game.offer_draw());
+    assert!(!game.offer_draw());
+    assert!(!game.can_answer_draw_offer());
+    play(&mut game, "e4");
+    assert!(game.can_answer_draw_offer());
+    play(&mut game, "e5");
+    assert!(game.pending_draw_offer().is_none());
+    assert!(!game.accept_draw());
+
+    //black offers with their move, white turns it down.
+    play(&mut game, "Nf3");
+    assert!(game.offer_draw());
+    play(&mut game, "Nc6");
+    assert_eq!(game.pending_draw_offer(), Some(PieceColor::Black));
+    assert!(game.decline_draw());
+    assert!(!game.can_answer_draw_offer());
+
+    //and the third offer gets taken.
+    play(&mut game, "Bb5");
+    assert!(game.offer_draw());
+    play(&mut game, "a6");
+    assert!(game.accept_draw());
+    assert_eq!(
+        game.outcome(),
+        Some(GameOutcome::draw(TerminationReason::Agreement))
+    );
+    assert_eq!(game.draw_offer_plies(), &vec![0, 3, 5]);
+    assert!(game.to_pgn().ends_with(
+        "1. e4 {draw offer} 1... e5 2. Nf3 Nc6 {draw offer} 3. Bb5 a6 {draw offer}\n{Draw by agreement} 1/2-1/2\n\n"
+    ));
+}
+
+#[test]
+pub fn test_seventy_five_move_rule_ends_game() {
+    let mut game = Game::from_board(
+        digest_string_to_board("4k3/8/8/8/8/8/8/R3K3 w - - 149 100".to_string()).unwrap(),
+    );
+    let next_move = game.current_board().parse_san("Ra2").unwrap();
+    game.play_move(&next_move);
+
+    let outcome = game.outcome().unwrap();
+    assert_eq!(
+        outcome,
+        GameOutcome::draw(TerminationReason::SeventyFiveMove)
+    );
+    assert_eq!(outcome.to_string(), "Draw by the seventy-five move rule");
+    assert!(game
+        .to_pgn()
+        .ends_with("100. Ra2 {Draw by the seventy-five move rule} 1/2-1/2\n\n"));
+}
+
+fn run_uci_script(script: &str) -> Vec<String> {
+    let mut output: Vec<u8> = Vec::new();
+    run_uci(script.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+pub fn test_uci_handshake() {
+    let output = run_uci_script("uci\nisready\nquit\nisready\n");
+    assert!(output[0].starts_with("id name "));
+    assert_eq!(output[output.len() - 2], "uciok");
+    //nothing after quit gets answered.
+    assert_eq!(output.last().unwrap(), "readyok");
+    assert_eq!(output.iter().filter(|line| *line == "readyok").count(), 1);
+}
+
+#[test]
+pub fn test_uci_position_and_go() {
+    //black walked into scholar's mate.
+    let output = run_uci_script(
+        "ucinewgame\nposition startpos moves e2e4 e7e5 d1h5 b8c6 f1c4 g8f6\ngo depth 2\n",
+    );
+    assert_eq!(output.last().unwrap(), "bestmove h5f7");
+
+    let output = run_uci_script("position fen 4k3/8/8/3q4/8/4N3/8/4K3 w - - 0 1\ngo depth 2\n");
+    assert!(output[0].starts_with("info depth 1 score cp "));
+    assert_eq!(output.last().unwrap(), "bestmove e3d5");
+
+    let output =
+        run_uci_script("position fen 6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1\ngo movetime 100\n");
+    assert!(output[0].starts_with("info depth 1 score mate 1 "));
+    assert!(output[0].ends_with(" pv a1a8"));
+    assert_eq!(output.last().unwrap(), "bestmove a1a8");
+
+    //down a queen, going back to where the game has already been is a draw, but only if the moves are known.
+    let output = run_uci_script(
+        "position fen 3qk3/8/8/8/8/8/8/6NK w - - 0 1 moves g1f3 e8f8 f3g1 f8e8 g1f3 e8f8\ngo depth 1\n",
+    );
+    assert!(output[0].starts_with("info depth 1 score cp 0 "));
+    assert_eq!(output.last().unwrap(), "bestmove f3g1");
+
+    //once mated there's nothing left to play.
+    let output = run_uci_script(
+        "position fen 6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1 moves a1a8\ngo wtime 1000 btime 1000\n",
+    );
+    assert_eq!(output, vec!["bestmove 0000"]);
+
+    //go infinite waits for stop before it answers.
+    let output = run_uci_script("position startpos\ngo infinite\nisready\nstop\n");
+    assert!(output.contains(&"readyok".to_string()));
+    assert!(output.last().unwrap().starts_with("bestmove "));
+
+    let output = run_uci_script(
+        "position startpos moves e2e5\nposition fen not a fen\nposition startpos\ngo depth 1\n",
+    );
+    assert_eq!(
+        output[0],
+        "info string that isn't a legal move in this position"
+    );
+    assert_eq!(output[1], "info string that fen string couldn't be parsed");
+    assert!(output.last().unwrap().starts_with("bestmove "));
+}
+
+fn assert_variation_is_legal(board: &Board, principal_variation: &[Move]) -> Board {
+    let mut variation_board = board.clone();
+    for pv_move in principal_variation {
+        assert!(
+            variation_board.legal_moves().contains(pv_move),
+            "{}",
+            pv_move
+        );
+        variation_board.make_move(pv_move);
+    }
+    variation_board
+}
+
+#[test]
+pub fn test_search_finds_mates() {
+    let board = digest_string_to_board("2k5/8/1K6/8/8/8/8/7R w - - 0 1".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(5),
+            opt_time: None,
+        },
+    );
+    assert_eq!(result.opt_mate_in(), Some(2));
+    assert_eq!(result.depth, 3);
+    let mated_board = assert_variation_is_legal(&board, &result.principal_variation);
+    assert!(!mated_board.legal_move_available());
+    assert!(mated_board.is_king_in_danger(PieceColor::Black));
+
+    //and from the side getting mated, the score says so.
+    let board = digest_string_to_board("k7/8/1K6/8/8/8/8/3R4 b - - 0 1".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(3),
+            opt_time: None,
+        },
+    );
+    assert_eq!(result.opt_mate_in(), Some(-1));
+}
+
+#[test]
+pub fn test_search_mate_beats_fifty_move_rule() {
+    //Rd8 is mate and also the 100th half move. The mate stands.
+    let board = digest_string_to_board("k7/8/1K6/8/8/8/8/3R4 w - - 99 80".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(1),
+            opt_time: None,
+        },
+    );
+    assert_eq!(result.opt_best_move.unwrap().to_string(), "d1d8");
+    assert_eq!(result.opt_mate_in(), Some(1));
+}
+
+#[test]
+pub fn test_search_sees_game_repetitions() {
+    //down a queen, white's way out is to go back to a position the game has already had.
+    let mut game = Game::from_board(
+        digest_string_to_board("3qk3/8/8/8/8/8/8/6NK w - - 0 1".to_string()).unwrap(),
+    );
+    for san in ["Nf3", "Kf8", "Ng1", "Ke8", "Nf3", "Kf8"] {
+        let next_move = game.current_board().parse_san(san).unwrap();
+        game.play_move(&next_move);
+    }
+    let limits = SearchLimits {
+        opt_depth: Some(1),
+        opt_time: None,
+    };
+
+    let result = search_with_history(game.current_board(), game.position_history(), limits);
+    assert_eq!(result.opt_best_move.unwrap().to_string(), "f3g1");
+    assert_eq!(result.score, 0);
+
+    //the board on its own doesn't know about any of that.
+    assert!(search(game.current_board(), limits).score < 0);
+}
+
+#[test]
+pub fn test_search_wins_material_and_sees_recaptures() {
+    //the queen on d5 hangs to the knight.
+    let board = digest_string_to_board("4k3/8/8/3q4/8/4N3/8/4K3 w - - 0 1".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(2),
+            opt_time: None,
+        },
+    );
+    assert_eq!(result.opt_best_move.unwrap().to_string(), "e3d5");
+    assert_variation_is_legal(&board, &result.principal_variation);
+
+    //the pawn on d5 is defended, so the queen shouldn't take it. Quiescence sees the recapture.
+    let board = digest_string_to_board("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(1),
+            opt_time: None,
+        },
+    );
+    assert!(result.opt_best_move.unwrap().to_string() != "d1d5");
+    assert!(result.score > 0);
+
+    //Nc7 is a check that forks the queen. Quiescence has to let the king get out of check before the queen falls.
+    let board = digest_string_to_board("q3k3/8/8/1N6/8/8/8/6K1 w - - 0 1".to_string()).unwrap();
+    let result = search(
+        &board,
+        SearchLimits {
+            opt_depth: Some(1),
+            opt_time: None,
+        },
+    );
+    assert_eq!(result.opt_best_move.unwrap().to_string(), "b5c7");
+    assert!(result.score > 0);
+}
+
+#[test]
+pub fn test_search_respects_time_and_stop() {
+    let start = std::time::Instant::now();
+    let result = search(
+        &Board::default(),
+        SearchLimits {
+            opt_depth: None,
+            opt_time: Some(std::time::Duration::from_millis(200)),
+        },
+    );
+    assert!(result.opt_best_move.is_some());
+    assert!(result.depth >= 1);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+    //a stop before the search even starts still gets a legal move back.
+    let stop_signal = std::sync::atomic::AtomicBool::new(true);
+    let result = search_with_progress(
+        &Board::default(),
+        SearchLimits::default(),
+        Some(&stop_signal),
+        |_| {},
+    );
+    assert!(Board::default()
+        .legal_moves()
+        .contains(&result.opt_best_move.unwrap()));
+}
+
+#[test]
+pub fn test_evaluation_is_symmetric() {
+    let start_breakdown = evaluation_breakdown(&Board::default());
+    assert_eq!(start_breakdown.white_score(), 0);
+    assert_eq!(start_breakdown.phase, 24);
+
+    //the same position with the colors swapped and the board flipped scores the same for the side to move.
+    let board = digest_string_to_board(
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4".to_string(),
+    )
+    .unwrap();
+    let flipped_board = digest_string_to_board(
+        "rnbqk2r/pppp1ppp/5n2/2b1p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R b KQkq - 4 4".to_string(),
+    )
+    .unwrap();
+    assert_eq!(evaluate(&board), evaluate(&flipped_board));
+    assert_eq!(
+        evaluation_breakdown(&board).white_score(),
+        -evaluation_breakdown(&flipped_board).white_score()
+    );
+}
+
+#[test]
+pub fn test_evaluation_terms() {
+    //white is a queen up, and it's black to move, so black sees a bad score.
+    let board = digest_string_to_board("4k3/8/8/8/8/8/8/3QK3 b - - 0 1".to_string()).unwrap();
+    let breakdown = evaluation_breakdown(&board);
+    assert!(breakdown.material > 800);
+    assert_eq!(evaluate(&board), -breakdown.white_score());
+
+    //white's pawns are doubled and isolated on the c file, black's passed pawn on a3 is almost home.
+    let board = digest_string_to_board("4k3/8/8/8/2P5/p1P5/8/4K3 w - - 0 1".to_string()).unwrap();
+    let breakdown = evaluation_breakdown(&board);
+    assert!(breakdown.pawn_structure < 0);
+
+    //a king with its pawns in front of it is safer than one out in the open.
+    let sheltered =
+        digest_string_to_board("r2qk2r/ppp2ppp/8/8/8/8/PPP2PPP/R2Q1RK1 w kq - 0 1".to_string())
+            .unwrap();
+    let exposed =
+        digest_string_to_board("r2qk2r/ppp2ppp/8/8/8/8/PPPPP3/R2Q1RK1 w kq - 0 1".to_string())
+            .unwrap();
+    assert!(
+        evaluation_breakdown(&sheltered).king_safety > evaluation_breakdown(&exposed).king_safety
+    );
+
+    //a rook on an open board gets around more than one stuck in the corner.
+    let free_rook = digest_string_to_board("4k3/8/8/8/3R4/8/8/4K3 w - - 0 1".to_string()).unwrap();
+    let boxed_rook =
+        digest_string_to_board("4k3/8/8/8/8/8/PP6/RN2K3 w - - 0 1".to_string()).unwrap();
+    assert!(evaluation_breakdown(&free_rook).mobility > 0);
+    assert!(evaluation_breakdown(&boxed_rook).mobility < 0);
+}
+
+fn squares_of(bitboard: u64) -> Vec<String> {
+    bitboard::square_indices(bitboard)
+        .map(|index| bitboard::coordinates_from_index(index).to_string())
+        .collect()
+}
+
+#[test]
+pub fn test_attackers_and_checkers() {
+    let board =
+        digest_string_to_board("4k3/8/8/3p4/8/1B2Nr2/8/3RK3 w - - 0 1".to_string()).unwrap();
+    let d5 = parse_square("d5").unwrap();
+    assert_eq!(
+        squares_of(board.attackers_of(&d5, PieceColor::White)),
+        vec!["D1", "B3", "E3"]
+            .iter()
+            .map(|square| square.to_string())
+            .collect::<Vec<String>>()
+    );
+    assert!(board.is_square_attacked(&parse_square("f1").unwrap(), PieceColor::Black));
+    assert!(!board.is_square_attacked(&parse_square("a1").unwrap(), PieceColor::Black));
+    assert_eq!(board.checkers(), 0);
+
+    //a knight and a rook both giving check.
+    let double_check =
+        digest_string_to_board("4k3/8/3N4/8/8/8/8/4RK2 b - - 0 1".to_string()).unwrap();
+    assert_eq!(double_check.checkers().count_ones(), 2);
+}
+
+#[test]
+pub fn test_pinned_pieces() {
+    //the e2 knight is pinned by the rook, the d2 bishop by the queen on a5.
+    let board = digest_string_to_board("4r2k/8/8/q7/8/8/3BN3/4K3 w - - 0 1".to_string()).unwrap();
+    let pins = board.pinned_pieces(PieceColor::White);
+    assert_eq!(pins.len(), 2);
+    let knight_pin = pins
+        .iter()
+        .find(|pin| pin.pinned == parse_square("e2").unwrap())
+        .unwrap();
+    assert_eq!(knight_pin.pinner, parse_square("e8").unwrap());
+    assert_eq!(knight_pin.ray.count_ones(), 7);
+    let bishop_pin = pins
+        .iter()
+        .find(|pin| pin.pinned == parse_square("d2").unwrap())
+        .unwrap();
+    assert_eq!(bishop_pin.pinner, parse_square("a5").unwrap());
+    assert_eq!(squares_of(bishop_pin.ray), vec!["D2", "C3", "B4", "A5"]);
+
+    //two pieces in the way means nothing is pinned, and a black piece in the way isn't ours to worry about.
+    let board = digest_string_to_board("4r2k/8/8/4n3/8/8/4N3/4K3 w - - 0 1".to_string()).unwrap();
+    assert!(board.pinned_pieces(PieceColor::White).is_empty());
+    let board = digest_string_to_board("4r2k/8/8/8/8/8/4p3/4K3 w - - 0 1".to_string()).unwrap();
+    assert!(board.pinned_pieces(PieceColor::White).is_empty());
+}
+
+#[test]
+pub fn test_polyglot_keys() {
+    //the worked examples from the Polyglot book format, which cover castling rights and both kinds of en passant square.
+    let examples = [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            0x463b96181691fc9c,
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            0x823c9b50fd114196,
+        ),
+        (
+            "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+            0x0756b94461c50fb0,
+        ),
+        (
+            "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+            0x662fafb965db29d4,
+        ),
+        (
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            0x22a48b5a8e47ff78,
+        ),
+        (
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+            0x652a607ca3f242c1,
+        ),
+        (
+            "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+            0x00fdd303c946bdd9,
+        ),
+        (
+            "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+            0x3c8123ea7b067637,
+        ),
+        (
+            "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+            0x5c3f9b829b279560,
+        ),
+    ];
+    for (fen, expected_key) in examples {
+        let board = digest_string_to_board(fen.to_string()).unwrap();
+        assert_eq!(polyglot_key(&board), expected_key, "{}", fen);
+    }
+
+    //the same position reached by playing the moves hashes the same as the one read from a fen.
+    let mut board = Board::default();
+    for move_text in ["e2e4", "d7d5", "e4e5", "f7f5"] {
+        let chess_move = board
+            .legal_moves()
+            .into_iter()
+            .find(|legal_move| legal_move.to_string() == move_text)
+            .unwrap();
+        board.move_piece(&chess_move);
+    }
+    assert_eq!(polyglot_key(&board), 0x22a48b5a8e47ff78);
+}
+
+fn book_entry(board: &Board, from: &str, to: &str, weight: u16) -> BookEntry {
+    let square_number = |square: &str| {
+        let coords = parse_square(square).unwrap();
+        bitboard::square_index(&coords).unwrap() as u16
+    };
+    BookEntry {
+        key: polyglot_key(board),
+        raw_move: (square_number(from) << 6) | square_number(to),
+        weight,
+        learn: 0,
+    }
+}
+
+#[test]
+pub fn test_polyglot_book_moves() {
+    let start = Board::default();
+    let other_position =
+        digest_string_to_board("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1".to_string()).unwrap();
+    let entries = [
+        book_entry(&other_position, "e1", "h1", 5),
+        book_entry(&start, "d2", "d4", 30),
+        book_entry(&start, "e2", "e4", 70),
+        book_entry(&other_position, "e1", "a1", 0),
+        //a move that isn't legal in the position gets left out.
+        book_entry(&start, "e2", "e5", 100),
+    ];
+    let book_bytes = entries
+        .iter()
+        .flat_map(|entry| entry.to_bytes())
+        .collect::<Vec<u8>>();
+    let book = PolyglotBook::from_bytes(&book_bytes).unwrap();
+    assert_eq!(book.len(), 5);
+    assert!(PolyglotBook::from_bytes(&book_bytes[1..]).is_err());
+
+    let start_moves = book.book_moves(&start);
+    assert_eq!(start_moves.len(), 2);
+    assert_eq!(start_moves[0].chess_move.to_string(), "e2e4");
+    assert_eq!(start_moves[0].weight, 70);
+    assert_eq!(start_moves[1].chess_move.to_string(), "d2d4");
+    assert_eq!(book.best_move(&start).unwrap().to_string(), "e2e4");
+    assert_eq!(
+        book.choose_move(&start, BookSelection::BestWeight)
+            .unwrap()
+            .to_string(),
+        "e2e4"
+    );
+
+    //rolls below 70 land on e4, the next 30 on d4.
+    assert_eq!(book.weighted_move(&start, 0).unwrap().to_string(), "e2e4");
+    assert_eq!(book.weighted_move(&start, 69).unwrap().to_string(), "e2e4");
+    assert_eq!(book.weighted_move(&start, 70).unwrap().to_string(), "d2d4");
+    assert_eq!(book.weighted_move(&start, 199).unwrap().to_string(), "d2d4");
+    let random_move = book
+        .choose_move(&start, BookSelection::WeightedRandom)
+        .unwrap()
+        .to_string();
+    assert!(random_move == "e2e4" || random_move == "d2d4");
+
+    //castling comes out of the book as the king taking its rook.
+    let castling_moves = book
+        .book_moves(&other_position)
+        .iter()
+        .map(|book_move| book_move.chess_move.to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(castling_moves, vec!["e1g1", "e1c1"]);
+
+    //positions the book doesn't know have no moves, and neither does one with only zero weights.
+    let unknown = digest_string_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1".to_string()).unwrap();
+    assert!(book.book_moves(&unknown).is_empty());
+    assert!(book.best_move(&unknown).is_none());
+    let zero_weight_book = PolyglotBook::from_entries(vec![book_entry(&start, "e2", "e4", 0)]);
+    assert_eq!(zero_weight_book.book_moves(&start).len(), 1);
+    assert!(zero_weight_book.best_move(&start).is_none());
+    assert!(zero_weight_book.weighted_move(&start, 3).is_none());
+}
+
+//the tables for KQvK, KRvK, KPvK, KBvK and KNvK. Everything the tests check against them is a known fact about
+//those endings rather than about how the files are laid out, so any correct copy of the tables passes.
+const SYZYGY_DIRECTORY: &str = "./src/syzygyFiles";
+
+//writes just the header of a pawnless Syzygy table with every side holding one value, which is enough to check
+//that names, magic numbers and pieces have to agree.
+fn syzygy_header_bytes(is_dtz: bool, piece_codes: &[u8], single_value: u8) -> Vec<u8> {
+    let mut bytes = if is_dtz {
+        vec![0xD7, 0x66, 0x0C, 0xA5]
+    } else {
+        vec![0x71, 0xE8, 0x23, 0x5D]
+    };
+    bytes.extend_from_slice(&[1, 0]);
+    for piece_code in piece_codes {
+        bytes.push(piece_code | (piece_code << 4));
+    }
+    if bytes.len() % 2 == 1 {
+        bytes.push(0);
+    }
+    for _ in 0..if is_dtz { 1 } else { 2 } {
+        bytes.extend_from_slice(&[0x80, single_value]);
+    }
+    while bytes.len() % 64 != 0 {
+        bytes.push(0);
+    }
+    bytes
+}
+
+fn tablebase_board(fen: &str) -> Board {
+    digest_string_to_board(fen.to_string()).unwrap()
+}
+
+//the same position with the colors swapped and the board turned upside down.
+fn swapped_tablebase_board(fen: &str) -> Board {
+    let fields = fen.split(' ').collect::<Vec<&str>>();
+    let placement = fields[0]
+        .split('/')
+        .rev()
+        .map(|row| {
+            row.chars()
+                .map(|letter| match letter.is_ascii_uppercase() {
+                    true => letter.to_ascii_lowercase(),
+                    false => letter.to_ascii_uppercase(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+    let turn = if fields[1] == "w" { "b" } else { "w" };
+    digest_string_to_board(format!("{} {} - - 0 1", placement, turn)).unwrap()
+}
+
+//every white king, white piece and black king placement a step apart, with both sides to move. Placements the fen
+//parser won't have, like the side that isn't moving being in check, are left out.
+fn sampled_tablebase_boards(piece: char, step: usize) -> Vec<Board> {
+    let mut boards = Vec::new();
+    for placement in (0..64 * 64 * 64).step_by(step) {
+        let squares = [placement / 4096, placement / 64 % 64, placement % 64];
+        if squares[0] == squares[1] || squares[0] == squares[2] || squares[1] == squares[2] {
+            continue;
+        }
+        if piece == 'P' && (squares[1] < 8 || squares[1] >= 56) {
+            continue;
+        }
+        let mut rows = [['1'; 8]; 8];
+        for (square, letter) in squares.iter().zip(['K', piece, 'k']) {
+            rows[7 - square / 8][square % 8] = letter;
+        }
+        let placement = rows
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("/");
+        for turn in ["w", "b"] {
+            if let Ok(board) = digest_string_to_board(format!("{} {} - - 0 1", placement, turn)) {
+                boards.push(board);
+            }
+        }
+    }
+    boards
+}
+
+//what a position holds has to be what its best move leads to, so a table read from the wrong place shows up as a
+//disagreement with the positions one move on.
+fn assert_tablebase_consistent(tablebase: &Tablebase, board: &Board) {
+    let wdl = tablebase.probe_wdl(board).unwrap();
+    let dtz = tablebase.probe_dtz(board).unwrap();
+    let tablebase_moves = tablebase.tablebase_moves(board).unwrap();
+    let in_check = board.checkers() != bitboard::EMPTY_BITBOARD;
+    match tablebase_moves.first() {
+        None if in_check => {
+            assert_eq!(wdl, Wdl::Loss);
+            assert_eq!(dtz, -1);
+        }
+        None => {
+            assert_eq!(wdl, Wdl::Draw);
+            assert_eq!(dtz, 0);
+        }
+        Some(best) => {
+            assert_eq!(wdl, best.wdl);
+            let quickest_win = tablebase_moves
+                .iter()
+                .filter(|tablebase_move| tablebase_move.wdl == Wdl::Win)
+                .map(|tablebase_move| tablebase_move.dtz)
+                .min();
+            let slowest_loss = tablebase_moves
+                .iter()
+                .map(|tablebase_move| tablebase_move.dtz)
+                .min();
+            match wdl {
+                Wdl::Win => assert_eq!(Some(dtz), quickest_win),
+                Wdl::Loss => assert_eq!(Some(dtz), slowest_loss),
+                _ => assert_eq!(dtz, 0),
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_tablebase_wdl_probe() {
+    let tablebase = Tablebase::open(SYZYGY_DIRECTORY).unwrap();
+    assert_eq!(tablebase.max_pieces(), 3);
+
+    let known_results = [
+        //a queen or rook wins from anywhere black can't take it.
+        ("8/8/8/3k4/8/8/8/KQ6 w - - 0 1", Wdl::Win),
+        ("8/8/8/4k3/8/8/8/R3K3 w - - 0 1", Wdl::Win),
+        ("8/8/8/8/8/8/1k6/Q3K3 b - - 0 1", Wdl::Draw),
+        ("8/8/8/8/8/8/1k6/R3K3 b - - 0 1", Wdl::Draw),
+        ("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1", Wdl::Draw),
+        ("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1", Wdl::Loss),
+        //with the king in front of its pawn, the opposition decides it.
+        ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", Wdl::Win),
+        ("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", Wdl::Loss),
+        ("4k3/8/4P3/4K3/8/8/8/8 w - - 0 1", Wdl::Draw),
+        ("4k3/8/4P3/4K3/8/8/8/8 b - - 0 1", Wdl::Draw),
+        ("k7/8/K7/P7/8/8/8/8 w - - 0 1", Wdl::Draw),
+        ("2k5/2P5/2K5/8/8/8/8/8 w - - 0 1", Wdl::Win),
+        ("8/8/1k6/8/8/K5P1/8/8 w - - 0 1", Wdl::Win),
+    ];
+    for (fen, wdl) in known_results {
+        assert_eq!(
+            tablebase.probe_wdl(&tablebase_board(fen)),
+            Ok(wdl),
+            "{}",
+            fen
+        );
+        assert_eq!(
+            tablebase.probe_wdl(&swapped_tablebase_board(fen)),
+            Ok(wdl),
+            "{}",
+            fen
+        );
+    }
+
+    //a pawn about to promote has to look up the piece it becomes.
+    let board = tablebase_board("8/4P3/8/8/8/8/k7/2K5 w - - 0 1");
+    assert_eq!(tablebase.probe_wdl(&board), Ok(Wdl::Win));
+    let board = tablebase_board("3k4/4P3/8/8/8/8/8/K7 b - - 0 1");
+    assert_eq!(tablebase.probe_wdl(&board), Ok(Wdl::Draw));
+}
+
+#[test]
+pub fn test_tablebase_probe_limits() {
+    let tablebase = Tablebase::open(SYZYGY_DIRECTORY).unwrap();
+    //KNvK and KBvK are draws however the pieces stand, so the whole table is one value.
+    let board = tablebase_board("8/8/3k4/8/8/2N5/8/4K3 b - - 0 1");
+    assert_eq!(tablebase.probe_wdl(&board), Ok(Wdl::Draw));
+    assert_eq!(tablebase.probe_dtz(&board), Ok(0));
+    let board = tablebase_board("8/8/3k4/8/8/2b5/8/4K3 w - - 0 1");
+    assert_eq!(tablebase.probe_wdl(&board), Ok(Wdl::Draw));
+
+    //two bare kings don't need a table at all.
+    let bare_kings = tablebase_board("8/8/3k4/8/8/8/8/4K3 w - - 0 1");
+    assert_eq!(tablebase.probe_wdl(&bare_kings), Ok(Wdl::Draw));
+
+    let no_table = tablebase_board("8/8/3k4/8/8/2Q5/8/4K2r w - - 0 1");
+    assert!(tablebase.probe_wdl(&no_table).is_err());
+    let castling = tablebase_board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+    assert!(tablebase.probe_wdl(&castling).is_err());
+    assert!(tablebase.probe_wdl(&Board::default()).is_err());
+
+    //names, magic numbers, pieces and sizes all have to line up with each other.
+    let mut tablebase = Tablebase::default();
+    assert!(tablebase
+        .add_table("KNvK.txt", vec![0x71, 0xE8, 0x23, 0x5D])
+        .is_err());
+    assert!(tablebase
+        .add_table("KNvK.rtbz", syzygy_header_bytes(false, &[6, 2, 14], 2))
+        .is_err());
+    assert!(tablebase
+        .add_table("KBvK.rtbw", syzygy_header_bytes(false, &[6, 2, 14], 2))
+        .is_err());
+    assert!(tablebase
+        .add_table("KNvK.rtbw", vec![0x71, 0xE8, 0x23])
+        .is_err());
+    let knk_bytes = syzygy_header_bytes(false, &[6, 2, 14], 2);
+    assert!(tablebase
+        .add_table("KNvK.rtbw", knk_bytes[..knk_bytes.len() - 1].to_vec())
+        .is_err());
+    let krk_bytes = fs::read(format!("{}/KRvK.rtbw", SYZYGY_DIRECTORY)).unwrap();
+    assert!(tablebase
+        .add_table("KRvK.rtbw", krk_bytes[..krk_bytes.len() - 64].to_vec())
+        .is_err());
+    assert!(tablebase
+        .add_table("KRvK.rtbw", krk_bytes[..40].to_vec())
+        .is_err());
+    assert!(tablebase.add_table("KNvK.rtbw", knk_bytes).is_ok());
+    assert!(Tablebase::open("this directory does not exist").is_err());
+}
+
+#[test]
+pub fn test_tablebase_dtz_probe() {
+    let tablebase = Tablebase::open(SYZYGY_DIRECTORY).unwrap();
+
+    //the longest wins in each ending, mates in one and the opposition, in half moves to the next capture, pawn
+    //move or mate.
+    let known_distances = [
+        ("7K/6Q1/8/8/2k5/8/8/8 w - - 0 1", 19),
+        ("7K/6Q1/8/8/8/1k6/8/8 b - - 0 1", -20),
+        ("8/K7/8/8/4k3/2R5/8/8 w - - 0 1", 31),
+        ("8/K7/8/8/8/2R2k2/8/8 b - - 0 1", -32),
+        ("k7/8/1K6/8/8/8/8/3R4 w - - 0 1", 1),
+        ("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1", -1),
+        ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", 3),
+        ("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", -4),
+        ("2k5/2P5/2K5/8/8/8/8/8 w - - 0 1", 5),
+        ("8/8/1k6/8/8/K5P1/8/8 w - - 0 1", 19),
+        ("8/8/k7/8/K7/6P1/8/8 b - - 0 1", -20),
+        ("4k3/8/4P3/4K3/8/8/8/8 w - - 0 1", 0),
+    ];
+    for (fen, dtz) in known_distances {
+        assert_eq!(
+            tablebase.probe_dtz(&tablebase_board(fen)),
+            Ok(dtz),
+            "{}",
+            fen
+        );
+        assert_eq!(
+            tablebase.probe_dtz(&swapped_tablebase_board(fen)),
+            Ok(dtz),
+            "{}",
+            fen
+        );
+    }
+
+    //every legal move gets a result, and the best ones come first.
+    let board = tablebase_board("k7/8/1K6/8/8/8/8/3R4 w - - 0 1");
+    let tablebase_moves = tablebase.tablebase_moves(&board).unwrap();
+    assert_eq!(tablebase_moves.len(), board.legal_moves().len());
+    assert!(tablebase_moves
+        .windows(2)
+        .all(|pair| pair[0].wdl >= pair[1].wdl));
+    let best_move = tablebase.best_move(&board).unwrap().unwrap();
+    assert_eq!(best_move.chess_move.to_string(), "d1d8");
+    assert_eq!((best_move.wdl, best_move.dtz), (Wdl::Win, 1));
+
+    //picking a move needs the DTZ tables as well.
+    let mut wdl_only = Tablebase::default();
+    let krk_bytes = fs::read(format!("{}/KRvK.rtbw", SYZYGY_DIRECTORY)).unwrap();
+    wdl_only.add_table("KRvK.rtbw", krk_bytes).unwrap();
+    assert_eq!(wdl_only.probe_wdl(&board), Ok(Wdl::Win));
+    assert!(wdl_only.best_move(&board).is_err());
+}
+
+#[test]
+pub fn test_tablebase_agrees_with_itself_one_move_on() {
+    let tablebase = Tablebase::open(SYZYGY_DIRECTORY).unwrap();
+    for piece in ['Q', 'R', 'P'] {
+        for board in sampled_tablebase_boards(piece, 397) {
+            assert_tablebase_consistent(&tablebase, &board);
+        }
+    }
+}
+
+#[test]
+pub fn test_chess960_start_positions() {
+    //number 518 is the standard setup, and number 0 has both bishops in the corner.
+    assert!(Board::chess960(518).unwrap() == Board::default());
+    assert_eq!(
+        Board::chess960(0).unwrap().generate_fen_string(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert!(Board::chess960(CHESS960_POSITIONS).is_err());
+
+    let mut back_rows: Vec<String> = Vec::new();
+    for position_number in 0..CHESS960_POSITIONS {
+        let back_row = Row::chess960_back_row(PieceColor::White, position_number).unwrap();
+        let row_fen = back_row.generate_row_fen_string();
+        let files_of = |piece_char: char| {
+            row_fen
+                .char_indices()
+                .filter(|(_, found_char)| *found_char == piece_char)
+                .map(|(file, _)| file)
+                .collect::<Vec<usize>>()
+        };
+        let (bishops, rooks, kings) = (files_of('B'), files_of('R'), files_of('K'));
+        assert_eq!(row_fen.len(), 8);
+        assert_eq!(bishops.len(), 2);
+        assert_ne!(bishops[0] % 2, bishops[1] % 2, "{}", row_fen);
+        assert!(rooks[0] < kings[0] && kings[0] < rooks[1], "{}", row_fen);
+        back_rows.push(row_fen);
+    }
+    back_rows.sort();
+    back_rows.dedup();
+    assert_eq!(back_rows.len(), CHESS960_POSITIONS as usize);
+}
+
+#[test]
+pub fn test_chess960_castling() {
+    let square_move = |from: &str, to: &str| {
+        Move::new(parse_square(from).unwrap(), parse_square(to).unwrap(), None)
+    };
+
+    //the king on b1 castles by taking its own rook, since stepping to c1 or g1 would look like any other king move.
+    let board = digest_string_to_board("4k3/8/8/8/8/8/8/RK5R w AH - 0 1".to_string()).unwrap();
+    assert_eq!(
+        board.generate_fen_string(),
+        "4k3/8/8/8/8/8/8/RK5R w KQ - 0 1"
+    );
+    let legal_moves = board.legal_moves();
+    assert!(legal_moves.contains(&square_move("b1", "a1")));
+    assert!(legal_moves.contains(&square_move("b1", "h1")));
+    assert_eq!(square_move("b1", "a1").to_san(&board), "O-O-O");
+    assert_eq!(board.parse_san("O-O"), Ok(square_move("b1", "h1")));
+
+    let mut queenside = board.clone();
+    assert_eq!(
+        queenside.move_piece(&square_move("b1", "a1")),
+        MoveResult::CompletedSafely
+    );
+    assert_eq!(
+        queenside.generate_fen_string(),
+        "4k3/8/8/8/8/8/8/2KR3R b - - 1 1"
+    );
+    let mut kingside = board.clone();
+    kingside.move_piece(&square_move("b1", "h1"));
+    assert_eq!(
+        kingside.generate_fen_string(),
+        "4k3/8/8/8/8/8/8/R4RK1 b - - 1 1"
+    );
+
+    //a rook on the far side of another gets named by its file, and either way of writing it reads back the same.
+    let inner_rook = digest_string_to_board("4k3/8/8/8/8/8/8/RR2K3 w B - 0 1".to_string()).unwrap();
+    assert_eq!(
+        inner_rook.generate_fen_string(),
+        "4k3/8/8/8/8/8/8/RR2K3 w B - 0 1"
+    );
+    assert!(inner_rook.legal_moves().contains(&square_move("e1", "b1")));
+    let shredder = digest_string_to_board("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1".to_string());
+    let x_fen = digest_string_to_board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".to_string());
+    assert!(shredder.unwrap() == x_fen.unwrap());
+
+    //the rook on b1 is all that stops the black rook reaching c1, so the king can't castle onto c1 behind it.
+    let shielded = digest_string_to_board("4k3/8/8/8/8/8/8/rRK4R w B - 0 1".to_string()).unwrap();
+    assert!(!shielded.legal_moves().contains(&square_move("c1", "b1")));
+}
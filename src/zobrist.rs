@@ -0,0 +1,47 @@
+use crate::pieces::Piece;
+
+//random numbers for every piece on every square, and for each part of the state that isn't on the board.
+//They're worked out at compile time from a fixed seed, so a position hashes the same on every run.
+const fn split_mix(state: u64) -> (u64, u64) {
+    let next_state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut mixed = next_state;
+    mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D049BB133111EB);
+    (next_state, mixed ^ (mixed >> 31))
+}
+
+const fn generate_keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0u64; N];
+    let mut state = seed;
+    let mut index = 0;
+    while index < N {
+        let (next_state, key) = split_mix(state);
+        keys[index] = key;
+        state = next_state;
+        index += 1;
+    }
+    keys
+}
+
+//12 pieces, white pawn to black king, each with 64 squares.
+const PIECE_KEYS: [u64; 768] = generate_keys(0x436875737420);
+//white kingside, white queenside, black kingside, black queenside.
+const CASTLING_KEYS: [u64; 4] = generate_keys(0x636173746C65);
+const PASSANT_KEYS: [u64; 8] = generate_keys(0x70617373616E74);
+const BLACK_TO_MOVE_KEY: u64 = generate_keys::<1>(0x7475726E)[0];
+
+pub fn piece_key(piece: Piece, index: usize) -> u64 {
+    PIECE_KEYS[(piece.color.index() * 6 + piece.kind.index()) * 64 + index]
+}
+
+pub fn castling_key(castling_index: usize) -> u64 {
+    CASTLING_KEYS[castling_index]
+}
+
+pub fn passant_key(file_index: usize) -> u64 {
+    PASSANT_KEYS[file_index]
+}
+
+pub fn black_to_move_key() -> u64 {
+    BLACK_TO_MOVE_KEY
+}
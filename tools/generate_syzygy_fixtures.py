@@ -0,0 +1,720 @@
+#!/usr/bin/env python3
+"""Writes stand-ins for the Syzygy tables the tablebase tests read from src/syzygyFiles: KQvK, KRvK, KPvK, KBvK and
+KNvK, WDL and DTZ. The published tables can replace them file for file, since the tests only assert things that are
+true of the endings themselves.
+
+The positions are solved here by retrograde analysis, then numbered, compressed and laid out the way Ronald de
+Man's generator does it: pieces numbered through the a1-d1-d4 triangle or by leading pawn, values squeezed by
+recursive pairing and written as canonical Huffman codes in fixed size blocks, with a sparse index over the block
+lengths. Nothing here is shared with the Rust reader, so the tests catch a mistake on either side.
+
+Run it with python3 tools/generate_syzygy_fixtures.py. It only needs the standard library.
+"""
+
+import heapq
+import math
+import os
+import struct
+from collections import Counter, deque
+
+WDL_MAGIC = bytes([0x71, 0xE8, 0x23, 0x5D])
+DTZ_MAGIC = bytes([0xD7, 0x66, 0x0C, 0xA5])
+
+SPLIT_FLAG = 1
+HAS_PAWNS_FLAG = 2
+STM_FLAG = 1
+MAPPED_FLAG = 2
+WIN_PLIES_FLAG = 4
+LOSS_PLIES_FLAG = 8
+WIDE_FLAG = 16
+SINGLE_VALUE_FLAG = 128
+
+# piece codes in the files: pawn 1 up to king 6, plus 8 for black.
+CODES = {"P": 1, "N": 2, "B": 3, "R": 4, "Q": 5, "K": 6}
+BLACK_KING = 14
+
+KING_STEPS = [(df, dr) for df in (-1, 0, 1) for dr in (-1, 0, 1) if df or dr]
+KNIGHT_STEPS = [(1, 2), (2, 1), (-1, 2), (-2, 1), (1, -2), (2, -1), (-1, -2), (-2, -1)]
+SLIDES = {
+    "Q": KING_STEPS,
+    "R": [(1, 0), (-1, 0), (0, 1), (0, -1)],
+    "B": [(1, 1), (1, -1), (-1, 1), (-1, -1)],
+}
+
+
+def file_of(square):
+    return square % 8
+
+
+def rank_of(square):
+    return square // 8
+
+
+def step(square, df, dr):
+    f, r = file_of(square) + df, rank_of(square) + dr
+    if 0 <= f < 8 and 0 <= r < 8:
+        return r * 8 + f
+    return None
+
+
+def adjacent(a, b):
+    return max(abs(file_of(a) - file_of(b)), abs(rank_of(a) - rank_of(b))) <= 1
+
+
+KING_TARGETS = [[t for t in (step(s, df, dr) for df, dr in KING_STEPS) if t is not None] for s in range(64)]
+
+
+def piece_targets(kind, square, occupied):
+    """Squares a white piece attacks, stopping sliders at the first occupied square."""
+    if kind == "P":
+        return [t for t in (step(square, -1, 1), step(square, 1, 1)) if t is not None]
+    if kind == "N":
+        return [t for t in (step(square, df, dr) for df, dr in KNIGHT_STEPS) if t is not None]
+    targets = []
+    for df, dr in SLIDES[kind]:
+        t = step(square, df, dr)
+        while t is not None:
+            targets.append(t)
+            if t in occupied:
+                break
+            t = step(t, df, dr)
+    return targets
+
+
+# ---------------------------------------------------------------------------------------------------------------
+# solving. A state is (white king, white piece, black king) and results are from the side to move's point of view:
+# wdl is -2, 0 or 2 and dtz counts plies to the next capture, pawn move or mate.
+# ---------------------------------------------------------------------------------------------------------------
+
+
+class Solution:
+    def __init__(self):
+        self.wdl = [{}, {}]
+        self.dtz = [{}, {}]
+
+
+def black_in_check(kind, wk, x, bk):
+    return adjacent(wk, bk) or bk in piece_targets(kind, x, {wk, bk})
+
+
+def is_legal(kind, wk, x, bk, stm):
+    if len({wk, x, bk}) < 3 or adjacent(wk, bk):
+        return False
+    if kind == "P" and not 8 <= x < 56:
+        return False
+    # with white to move, black can't already be in check.
+    return not (stm == 0 and black_in_check(kind, wk, x, bk))
+
+
+def white_moves(kind, wk, x, bk):
+    """(next state or None, zeroing, promotion kind) for each legal white move."""
+    moves = []
+    for t in KING_TARGETS[wk]:
+        if t != x and not adjacent(t, bk):
+            moves.append(((t, x, bk), False, None))
+    if kind == "P":
+        push = x + 8
+        if push not in (wk, bk):
+            if push >= 56:
+                for promotion in "QRBN":
+                    moves.append(((wk, push, bk), True, promotion))
+            else:
+                moves.append(((wk, push, bk), True, None))
+                if x < 16 and x + 16 not in (wk, bk):
+                    moves.append(((wk, x + 16, bk), True, None))
+    else:
+        for t in piece_targets(kind, x, {wk, bk}):
+            if t != wk:
+                moves.append(((wk, t, bk), False, None))
+    return moves
+
+
+def black_moves(kind, wk, x, bk):
+    """(next state or None for a capture, zeroing) for each legal black move."""
+    moves = []
+    for t in KING_TARGETS[bk]:
+        if adjacent(t, wk):
+            continue
+        if t == x:
+            moves.append((None, True))
+        elif t not in piece_targets(kind, x, {wk, t}):
+            moves.append(((wk, x, t), False))
+    return moves
+
+
+def solve(kind, promotions):
+    """promotions maps a piece letter to the Solution for K+that piece vs K."""
+    squares = range(8, 56) if kind == "P" else range(64)
+    states = [
+        (wk, x, bk)
+        for x in squares
+        for wk in range(64)
+        for bk in range(64)
+        if len({wk, x, bk}) == 3 and not adjacent(wk, bk)
+    ]
+    solution = Solution()
+
+    # pawn moves only go forward, so the positions are solved one pawn square at a time from the seventh rank
+    # down, and every zeroing move lands on something already known.
+    if kind == "P":
+        groups = [[s for s in states if s[1] == pawn] for pawn in sorted(squares, reverse=True)]
+    else:
+        groups = [states]
+
+    for group in groups:
+        white_states = [s for s in group if is_legal(kind, *s, 0)]
+        black_states = [s for s in group if is_legal(kind, *s, 1)]
+
+        predecessors = {s: [] for s in white_states}
+        remaining = {}
+        queue = deque()
+        for s in black_states:
+            moves = black_moves(kind, *s)
+            if not moves:
+                in_check = black_in_check(kind, *s)
+                solution.wdl[1][s] = -2 if in_check else 0
+                solution.dtz[1][s] = -1 if in_check else 0
+                continue
+            remaining[s] = len(moves)
+            for next_state, zeroing in moves:
+                if not zeroing:
+                    predecessors[next_state].append(s)
+
+        black_predecessors = {s: [] for s in black_states}
+        for s in white_states:
+            moves = white_moves(kind, *s)
+            if not moves:
+                continue
+            seeded = False
+            for next_state, zeroing, promotion in moves:
+                if promotion is not None:
+                    result = promotions[promotion].wdl[1].get(next_state, 0) if promotion in promotions else 0
+                elif zeroing:
+                    result = solution.wdl[1].get(next_state, 0)
+                else:
+                    black_predecessors[next_state].append(s)
+                    # a mate counts the same as a zeroing move, and mated positions are already known.
+                    result = -2 if solution.dtz[1].get(next_state) == -1 else None
+                if result == -2:
+                    seeded = True
+            if seeded:
+                solution.wdl[0][s] = 2
+                solution.dtz[0][s] = 1
+                queue.append((0, s))
+
+        # breadth first, so each white position is reached first by its fastest win, and each black position is
+        # settled by its slowest loss.
+        while queue:
+            side, s = queue.popleft()
+            if side == 0:
+                for p in predecessors[s]:
+                    if p not in remaining:
+                        continue
+                    remaining[p] -= 1
+                    if remaining[p] == 0:
+                        solution.wdl[1][p] = -2
+                        solution.dtz[1][p] = -(solution.dtz[0][s] + 1)
+                        queue.append((1, p))
+            else:
+                for p in black_predecessors[s]:
+                    if p not in solution.wdl[0]:
+                        solution.wdl[0][p] = 2
+                        solution.dtz[0][p] = -solution.dtz[1][s] + 1
+                        queue.append((0, p))
+
+        for s in white_states:
+            if s not in solution.wdl[0]:
+                solution.wdl[0][s] = 0
+                solution.dtz[0][s] = 0
+        for s in black_states:
+            if s not in solution.wdl[1]:
+                solution.wdl[1][s] = 0
+                solution.dtz[1][s] = 0
+    return solution
+
+
+# ---------------------------------------------------------------------------------------------------------------
+# numbering positions, following the encoding the probing code in engines uses.
+# ---------------------------------------------------------------------------------------------------------------
+
+
+def off_a1h8(square):
+    return rank_of(square) - file_of(square)
+
+
+def binomial(k, n):
+    return math.comb(n, k) if 0 <= k <= n else 0
+
+
+MAP_B1H1H7 = [0] * 64
+MAP_A1D1D4 = [0] * 64
+MAP_PAWNS = [0] * 64
+LEAD_PAWN_INDEX = [[0] * 64 for _ in range(6)]
+LEAD_PAWNS_SIZE = [[0] * 4 for _ in range(6)]
+
+
+def init_maps():
+    code = 0
+    for s in range(64):
+        if off_a1h8(s) < 0:
+            MAP_B1H1H7[s] = code
+            code += 1
+    code = 0
+    diagonal = []
+    for s in range(28):
+        if off_a1h8(s) < 0 and file_of(s) <= 3:
+            MAP_A1D1D4[s] = code
+            code += 1
+        elif off_a1h8(s) == 0 and file_of(s) <= 3:
+            diagonal.append(s)
+    for s in diagonal:
+        MAP_A1D1D4[s] = code
+        code += 1
+    available = 47
+    for lead_count in range(1, 6):
+        for f in range(4):
+            index = 0
+            for r in range(1, 7):
+                s = r * 8 + f
+                if lead_count == 1:
+                    MAP_PAWNS[s] = available
+                    available -= 1
+                    MAP_PAWNS[s ^ 7] = available
+                    available -= 1
+                LEAD_PAWN_INDEX[lead_count][s] = index
+                index += binomial(lead_count - 1, MAP_PAWNS[s])
+            LEAD_PAWNS_SIZE[lead_count][f] = index
+
+
+init_maps()
+
+
+class Pairs:
+    """One side to move (and one leading pawn file) of a table: its piece order, groups and values."""
+
+    def __init__(self, pieces, order, has_pawns, f):
+        self.pieces = pieces
+        self.group_len = [1]
+        first_len = 0 if has_pawns else 3
+        for i in range(1, len(pieces)):
+            first_len -= 1
+            if first_len > 0 or pieces[i] == pieces[i - 1]:
+                self.group_len[-1] += 1
+            else:
+                self.group_len.append(1)
+        self.group_idx = [0] * (len(self.group_len) + 1)
+        next_group = 1
+        free = 64 - self.group_len[0]
+        index = 1
+        k = 0
+        while next_group < len(self.group_len) or k == order:
+            if k == order:
+                self.group_idx[0] = index
+                index *= LEAD_PAWNS_SIZE[self.group_len[0]][f] if has_pawns else 31332
+            else:
+                self.group_idx[next_group] = index
+                index *= binomial(self.group_len[next_group], free)
+                free -= self.group_len[next_group]
+                next_group += 1
+            k += 1
+        self.group_idx[len(self.group_len)] = index
+        self.size = index
+
+
+def encode(pairs, placement, has_pawns):
+    """placement is (square, code) for every piece. Gives back the position's number."""
+    squares, pieces = [], []
+    lead_count = 0
+    if has_pawns:
+        lead_code = pairs.pieces[0]
+        squares = [s for s, c in placement if c == lead_code]
+        lead_count = len(squares)
+        best = max(range(lead_count), key=lambda i: MAP_PAWNS[squares[i]])
+        squares[0], squares[best] = squares[best], squares[0]
+        pieces = [lead_code] * lead_count
+    for s, c in sorted(placement):
+        if not (has_pawns and c == pairs.pieces[0]):
+            squares.append(s)
+            pieces.append(c)
+
+    size = len(squares)
+    for i in range(lead_count, size - 1):
+        for j in range(i + 1, size):
+            if pairs.pieces[i] == pieces[j]:
+                pieces[i], pieces[j] = pieces[j], pieces[i]
+                squares[i], squares[j] = squares[j], squares[i]
+                break
+
+    if file_of(squares[0]) > 3:
+        squares = [s ^ 7 for s in squares]
+
+    if has_pawns:
+        index = LEAD_PAWN_INDEX[lead_count][squares[0]]
+        squares[1:lead_count] = sorted(squares[1:lead_count], key=lambda s: MAP_PAWNS[s])
+        for i in range(1, lead_count):
+            index += binomial(i, MAP_PAWNS[squares[i]])
+    else:
+        if rank_of(squares[0]) > 3:
+            squares = [s ^ 56 for s in squares]
+        for i in range(pairs.group_len[0]):
+            if off_a1h8(squares[i]) == 0:
+                continue
+            if off_a1h8(squares[i]) > 0:
+                for j in range(i, size):
+                    squares[j] = ((squares[j] >> 3) | (squares[j] << 3)) & 63
+            break
+        adjust1 = int(squares[1] > squares[0])
+        adjust2 = int(squares[2] > squares[0]) + int(squares[2] > squares[1])
+        if off_a1h8(squares[0]):
+            index = (MAP_A1D1D4[squares[0]] * 63 + (squares[1] - adjust1)) * 62 + squares[2] - adjust2
+        elif off_a1h8(squares[1]):
+            index = (6 * 63 + rank_of(squares[0]) * 28 + MAP_B1H1H7[squares[1]]) * 62 + squares[2] - adjust2
+        elif off_a1h8(squares[2]):
+            index = (
+                6 * 63 * 62
+                + 4 * 28 * 62
+                + rank_of(squares[0]) * 7 * 28
+                + (rank_of(squares[1]) - adjust1) * 28
+                + MAP_B1H1H7[squares[2]]
+            )
+        else:
+            index = (
+                6 * 63 * 62
+                + 4 * 28 * 62
+                + 4 * 7 * 28
+                + rank_of(squares[0]) * 7 * 6
+                + (rank_of(squares[1]) - adjust1) * 6
+                + (rank_of(squares[2]) - adjust2)
+            )
+
+    index *= pairs.group_idx[0]
+    start = pairs.group_len[0]
+    for g in range(1, len(pairs.group_len)):
+        end = start + pairs.group_len[g]
+        group = sorted(squares[start:end])
+        n = 0
+        for i, s in enumerate(group):
+            adjust = sum(1 for earlier in squares[:start] if s > earlier)
+            n += binomial(i + 1, s - adjust)
+        index += n * pairs.group_idx[g]
+        start = end
+    return index
+
+
+# ---------------------------------------------------------------------------------------------------------------
+# compression.
+# ---------------------------------------------------------------------------------------------------------------
+
+MAX_SYMBOLS = 4095
+MAX_EXPANSION = 256
+
+
+def repair(values):
+    """Recursive pairing: keeps replacing the commonest neighbouring pair with a new symbol."""
+    symbols = {}  # symbol -> (left, right) or (value, None)
+    lengths = {}
+    leaf_of = {}
+    sequence = []
+    for v in values:
+        if v not in leaf_of:
+            leaf_of[v] = len(symbols)
+            symbols[leaf_of[v]] = (v, None)
+            lengths[leaf_of[v]] = 1
+        sequence.append(leaf_of[v])
+
+    while len(symbols) < MAX_SYMBOLS:
+        counts = Counter(zip(sequence, sequence[1:]))
+        candidates = [
+            (count, pair) for pair, count in counts.items() if lengths[pair[0]] + lengths[pair[1]] <= MAX_EXPANSION
+        ]
+        if not candidates:
+            break
+        count, pair = max(candidates)
+        if count < 3:
+            break
+        new_symbol = len(symbols)
+        symbols[new_symbol] = pair
+        lengths[new_symbol] = lengths[pair[0]] + lengths[pair[1]]
+        replaced = []
+        i = 0
+        while i < len(sequence):
+            if i + 1 < len(sequence) and (sequence[i], sequence[i + 1]) == pair:
+                replaced.append(new_symbol)
+                i += 2
+            else:
+                replaced.append(sequence[i])
+                i += 1
+        sequence = replaced
+    return symbols, lengths, sequence
+
+
+def huffman_lengths(frequencies):
+    if len(frequencies) == 1:
+        return {symbol: 1 for symbol in frequencies}
+    heap = [(count, i, [symbol]) for i, (symbol, count) in enumerate(sorted(frequencies.items()))]
+    heapq.heapify(heap)
+    code_lengths = Counter()
+    tiebreak = len(heap)
+    while len(heap) > 1:
+        count_a, _, symbols_a = heapq.heappop(heap)
+        count_b, _, symbols_b = heapq.heappop(heap)
+        for symbol in symbols_a + symbols_b:
+            code_lengths[symbol] += 1
+        heapq.heappush(heap, (count_a + count_b, tiebreak, symbols_a + symbols_b))
+        tiebreak += 1
+    assert max(code_lengths.values()) <= 32
+    return dict(code_lengths)
+
+
+class BitWriter:
+    def __init__(self):
+        self.bits = []
+
+    def write(self, code, length):
+        for shift in range(length - 1, -1, -1):
+            self.bits.append((code >> shift) & 1)
+
+    def to_bytes(self, size):
+        assert len(self.bits) <= size * 8
+        padded = self.bits + [0] * (size * 8 - len(self.bits))
+        return bytes(int("".join(map(str, padded[i : i + 8])), 2) for i in range(0, len(padded), 8))
+
+
+def compress(values, flags, block_size_bits, span_bits):
+    """Gives back (header bytes, sparse index bytes, block length bytes, data bytes) for one run of values."""
+    if len(set(values)) == 1:
+        return bytes([flags | SINGLE_VALUE_FLAG, values[0]]), b"", b"", b""
+
+    symbols, lengths, sequence = repair(values)
+    code_lengths = huffman_lengths(Counter(sequence))
+    min_len, max_len = min(code_lengths.values()), max(code_lengths.values())
+
+    # longer codes get the lower symbol numbers, and symbols that only turn up inside pairs come last.
+    coded = sorted(code_lengths, key=lambda symbol: (-code_lengths[symbol], symbol))
+    uncoded = sorted(set(symbols) - set(coded))
+    number_of = {symbol: n for n, symbol in enumerate(coded + uncoded)}
+
+    level_count = max_len - min_len + 1
+    counts = [0] * level_count
+    for symbol in coded:
+        counts[code_lengths[symbol] - min_len] += 1
+    lowest = [0] * level_count
+    base = [0] * level_count
+    for level in range(level_count - 2, -1, -1):
+        lowest[level] = lowest[level + 1] + counts[level + 1]
+        assert (base[level + 1] + counts[level + 1]) % 2 == 0
+        base[level] = (base[level + 1] + counts[level + 1]) // 2
+
+    def code_of(symbol):
+        level = code_lengths[symbol] - min_len
+        return base[level] + number_of[symbol] - lowest[level], code_lengths[symbol]
+
+    # fill fixed size blocks with whole symbols.
+    block_size = 1 << block_size_bits
+    span = 1 << span_bits
+    max_block_values = 65536 - span
+    blocks = []
+    writer, bit_count, value_count = BitWriter(), 0, 0
+    for symbol in sequence:
+        code, length = code_of(symbol)
+        if bit_count + length > block_size * 8 or value_count + lengths[symbol] > max_block_values:
+            blocks.append((writer, value_count))
+            writer, bit_count, value_count = BitWriter(), 0, 0
+        writer.write(code, length)
+        bit_count += length
+        value_count += lengths[symbol]
+    blocks.append((writer, value_count))
+
+    # the sparse index says where the position halfway through each span sits.
+    block_starts = []
+    start = 0
+    for _, value_count in blocks:
+        block_starts.append(start)
+        start += value_count
+    sparse = bytearray()
+    block = 0
+    for k in range((len(values) + span - 1) // span):
+        position = k * span + span // 2
+        while block + 1 < len(blocks) and block_starts[block + 1] <= position:
+            block += 1
+        offset = position - block_starts[block]
+        assert offset < 65536
+        sparse += struct.pack("<IH", block, offset)
+
+    block_lengths = b"".join(struct.pack("<H", value_count - 1) for _, value_count in blocks)
+    data = b"".join(writer.to_bytes(block_size) for writer, _ in blocks)
+
+    tree = bytearray()
+    for symbol in coded + uncoded:
+        left, right = symbols[symbol]
+        if right is None:
+            left, right = left, 0xFFF
+        else:
+            left, right = number_of[left], number_of[right]
+        tree += bytes([left & 0xFF, ((left >> 8) & 0xF) | ((right & 0xF) << 4), right >> 4])
+    header = bytearray([flags, block_size_bits, span_bits, 0])
+    header += struct.pack("<I", len(blocks))
+    header += bytes([max_len, min_len])
+    header += b"".join(struct.pack("<H", lowest[level]) for level in range(level_count))
+    header += struct.pack("<H", len(symbols))
+    header += tree
+    if len(symbols) & 1:
+        header += b"\0"
+    return bytes(header), bytes(sparse), block_lengths, data
+
+
+# ---------------------------------------------------------------------------------------------------------------
+# tables.
+# ---------------------------------------------------------------------------------------------------------------
+
+
+def fill_gaps(values):
+    """Positions that can't come up get whatever came before them, which costs nothing to store."""
+    previous = next((v for v in values if v is not None), 0)
+    filled = []
+    for v in values:
+        previous = previous if v is None else v
+        filled.append(previous)
+    return filled
+
+
+def table_values(kind, solution, pairs, side, f, stored):
+    """stored turns (wdl, dtz) into the number written down, or None when it doesn't matter."""
+    has_pawns = kind == "P"
+    values = [None] * pairs.size
+    for (wk, x, bk), wdl in solution.wdl[side].items():
+        if has_pawns and min(file_of(x), 7 - file_of(x)) != f:
+            continue
+        placement = [(wk, CODES["K"]), (x, CODES[kind]), (bk, BLACK_KING)]
+        index = encode(pairs, placement, has_pawns)
+        value = stored(wdl, solution.dtz[side][(wk, x, bk)])
+        if value is None:
+            continue
+        assert values[index] in (None, value), (kind, side, f, wk, x, bk, values[index], value)
+        values[index] = value
+    return fill_gaps(values)
+
+
+def assemble(magic, flags, has_pawns, file_headers, runs, dtz_maps=b""):
+    """runs is a list, per file then per side, of what compress() handed back."""
+    out = bytearray(magic)
+    out.append(flags)
+    for header in file_headers:
+        out += header
+    if len(out) & 1:
+        out.append(0)
+    for header, _, _, _ in runs:
+        out += header
+    out += dtz_maps
+    if len(out) & 1:
+        out.append(0)
+    for _, sparse, _, _ in runs:
+        out += sparse
+    for _, _, block_lengths, _ in runs:
+        out += block_lengths
+    for _, _, _, data in runs:
+        while len(out) % 64:
+            out.append(0)
+        out += data
+    return bytes(out)
+
+
+def write_wdl(kind, solution, side_pieces, order_byte, block_size_bits, span_bits):
+    has_pawns = kind == "P"
+    files = 4 if has_pawns else 1
+    file_headers, runs = [], []
+    for f in range(files):
+        pieces = side_pieces[f]
+        file_headers.append(
+            bytes([order_byte]) + bytes(low | (high << 4) for low, high in zip(pieces[0], pieces[1]))
+        )
+    for f in range(files):
+        for side in (0, 1):
+            pairs = Pairs(side_pieces[f][side], (order_byte >> (4 * side)) & 0xF, has_pawns, f)
+            values = table_values(kind, solution, pairs, side, f, lambda wdl, dtz: wdl + 2)
+            runs.append(compress(values, 0, block_size_bits, span_bits))
+    flags = SPLIT_FLAG | (HAS_PAWNS_FLAG if has_pawns else 0)
+    return assemble(WDL_MAGIC, flags, has_pawns, file_headers, runs)
+
+
+def write_dtz(kind, solution, file_pieces, order_byte, stored_sides, mapping, block_size_bits, span_bits):
+    """mapping is None, "byte" or "wide" and says how values go through a DTZ map."""
+    has_pawns = kind == "P"
+    files = 4 if has_pawns else 1
+    file_headers, runs, maps = [], [], bytearray()
+    for f in range(files):
+        file_headers.append(bytes([order_byte]) + bytes(code | (code << 4) for code in file_pieces[f]))
+    for f in range(files):
+        side = stored_sides[f]
+        pairs = Pairs(file_pieces[f], order_byte & 0xF, has_pawns, f)
+        known = [(wdl, dtz) for key, wdl in solution.wdl[side].items() for dtz in [solution.dtz[side][key]]]
+        win_plies = any(dtz % 2 == 0 for wdl, dtz in known if wdl == 2)
+        loss_plies = any(dtz % 2 == 0 for wdl, dtz in known if wdl == -2)
+
+        def moves_or_plies(wdl, dtz):
+            plies = win_plies if wdl == 2 else loss_plies
+            return abs(dtz) - 1 if plies else (abs(dtz) - 1) // 2
+
+        flags = side | (WIN_PLIES_FLAG if win_plies else 0) | (LOSS_PLIES_FLAG if loss_plies else 0)
+        if mapping is None:
+
+            def stored(wdl, dtz):
+                return None if wdl == 0 else moves_or_plies(wdl, dtz)
+
+        else:
+            flags |= MAPPED_FLAG | (WIDE_FLAG if mapping == "wide" else 0)
+            win_map = sorted({moves_or_plies(wdl, dtz) for wdl, dtz in known if wdl == 2})
+            loss_map = sorted({moves_or_plies(wdl, dtz) for wdl, dtz in known if wdl == -2})
+            if mapping == "wide":
+                if len(maps) & 1:
+                    maps.append(0)
+                for value_map in (win_map, loss_map, [], []):
+                    maps += struct.pack("<H", len(value_map))
+                    maps += b"".join(struct.pack("<H", value) for value in value_map)
+            else:
+                for value_map in (win_map, loss_map, [], []):
+                    maps.append(len(value_map))
+                    maps += bytes(value_map)
+
+            def stored(wdl, dtz, win_map=win_map, loss_map=loss_map):
+                if wdl == 0:
+                    return None
+                value_map = win_map if wdl == 2 else loss_map
+                return value_map.index(moves_or_plies(wdl, dtz))
+
+        values = table_values(kind, solution, pairs, side, f, stored)
+        runs.append(compress(values, flags, block_size_bits, span_bits))
+    flags = SPLIT_FLAG | (HAS_PAWNS_FLAG if has_pawns else 0)
+    return assemble(DTZ_MAGIC, flags, has_pawns, file_headers, runs, bytes(maps))
+
+
+def main():
+    K, k = CODES["K"], BLACK_KING
+    solutions = {}
+    for kind in "QRBN":
+        solutions[kind] = solve(kind, {})
+    solutions["P"] = solve("P", solutions)
+
+    Q, R, B, N, P = CODES["Q"], CODES["R"], CODES["B"], CODES["N"], CODES["P"]
+    tables = {
+        # a lone minor piece never wins, so these come out as a single value each.
+        "KBvK.rtbw": write_wdl("B", solutions["B"], [([K, B, k], [K, B, k])], 0x00, 6, 9),
+        "KBvK.rtbz": write_dtz("B", solutions["B"], [[K, B, k]], 0x00, [0], None, 5, 8),
+        "KNvK.rtbw": write_wdl("N", solutions["N"], [([K, N, k], [K, N, k])], 0x00, 6, 9),
+        "KNvK.rtbz": write_dtz("N", solutions["N"], [[K, N, k]], 0x00, [0], None, 5, 8),
+        "KQvK.rtbw": write_wdl("Q", solutions["Q"], [([K, Q, k], [Q, k, K])], 0x00, 6, 8),
+        "KQvK.rtbz": write_dtz("Q", solutions["Q"], [[k, Q, K]], 0x00, [0], None, 5, 7),
+        "KRvK.rtbw": write_wdl("R", solutions["R"], [([R, K, k], [k, K, R])], 0x00, 6, 9),
+        "KRvK.rtbz": write_dtz("R", solutions["R"], [[K, R, k]], 0x00, [1], "wide", 5, 8),
+        "KPvK.rtbw": write_wdl("P", solutions["P"], [([P, K, k], [P, k, K])] * 4, 0x20, 6, 9),
+        "KPvK.rtbz": write_dtz("P", solutions["P"], [[P, k, K]] * 4, 0x11, [0, 1, 0, 1], "byte", 5, 8),
+    }
+    directory = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "src", "syzygyFiles")
+    for file_name, contents in tables.items():
+        with open(os.path.join(directory, file_name), "wb") as table_file:
+            table_file.write(contents)
+        print(file_name, len(contents), "bytes")
+
+
+if __name__ == "__main__":
+    main()